@@ -0,0 +1,270 @@
+use clap::{Args, Parser, Subcommand};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::utils::cargo::BuildOptions;
+use crate::utils::crates_version::VersionPin;
+use crate::utils::engine::{EngineVersion, RenderingMethod};
+use crate::utils::gdextension::{ExtensionEntries, PlatformSpec};
+use crate::utils::layout::ProjectLayout;
+
+// Running godust without a subcommand (e.g. `godust --name my_game ...`)
+// behaves exactly like `godust new`, so existing scripts keep working.
+#[derive(Parser, Debug)]
+#[command(version, about, args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[command(flatten)]
+    pub new: Option<ProjectArgs>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Create a new project in a new directory
+    New(ProjectArgs),
+    /// Generate a project inside an existing directory, or add a crate to a Godot project
+    Init(InitArgs),
+    /// List available resources
+    List {
+        #[command(subcommand)]
+        item: ListCommand,
+    },
+    /// Add a new item to an existing project
+    Add {
+        #[command(subcommand)]
+        item: AddCommand,
+    },
+}
+
+// Options shared by every command that generates a project. Name, engine
+// and rendering method are prompted for when missing and godust runs in a
+// terminal.
+#[derive(Args, Debug, Clone, Default)]
+pub struct ProjectArgs {
+    /// Project name
+    #[arg(short, long)]
+    pub name: Option<String>,
+    /// Godot engine version (e.g. 4.3, 4.3.1 or 4.4-beta2)
+    #[arg(short, long)]
+    pub engine: Option<EngineVersion>,
+    /// Rendering method
+    #[arg(short, long, value_enum)]
+    pub rendering_method: Option<RenderingMethod>,
+    /// Template (available: blank, blank_ecs, or any in the user templates directory) [default: blank]
+    #[arg(short, long)]
+    pub template: Option<String>,
+    /// Render the template found in this directory instead of a named one
+    #[arg(long, value_name = "DIR")]
+    pub template_path: Option<PathBuf>,
+    /// Set a template variable (repeatable, see `godust list templates`)
+    #[arg(long = "var", value_name = "KEY=VALUE", value_parser = parse_variable)]
+    pub variables: Vec<(String, String)>,
+    /// How to organise the Rust code
+    #[arg(long, value_enum, default_value_t)]
+    pub layout: ProjectLayout,
+    /// Add a binary tool crate to a workspace layout (repeatable)
+    #[arg(long = "tool", value_name = "NAME", value_parser = parse_tool_name)]
+    pub tools: Vec<String>,
+    /// Godot project directory inside the project, `{name}` and `{kebab_name}` are replaced [default: {kebab_name}]
+    #[arg(long, value_name = "DIR")]
+    pub godot_dir: Option<String>,
+    /// GDExtension crate directory inside the project, with the same placeholders [default: {name}_core]
+    #[arg(long, value_name = "DIR")]
+    pub core_dir: Option<String>,
+    /// Derive every generated UID from this seed, for reproducible output
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Don't contact the registry; use cached or known-good dependency versions
+    #[arg(long)]
+    pub offline: bool,
+    /// Print the files that would be generated, without writing anything or building
+    #[arg(long)]
+    pub dry_run: bool,
+    /// Don't build the Rust library after generating the project
+    #[arg(long)]
+    pub no_build: bool,
+    /// Build the Rust library with the release profile
+    #[arg(long, conflicts_with = "no_build")]
+    pub release: bool,
+    /// Build the Rust library for this target triple (e.g. aarch64-linux-android)
+    #[arg(long, value_name = "TRIPLE", conflicts_with = "no_build")]
+    pub target: Option<String>,
+    /// Platforms the .gdextension has libraries for, optionally narrowed to one architecture (e.g. linux,android.arm64) [default: linux,windows,macos]
+    #[arg(long, value_name = "PLATFORMS", value_delimiter = ',')]
+    pub platforms: Vec<PlatformSpec>,
+    /// Add an editor icon for a class to the .gdextension (repeatable)
+    #[arg(long = "extension-icon", value_name = "CLASS=PATH", value_parser = parse_extension_icon)]
+    pub extension_icons: Vec<(String, String)>,
+    /// Add a library the extension depends on to the .gdextension, by crate name (repeatable)
+    #[arg(long = "extension-dependency", value_name = "NAME", value_parser = parse_tool_name)]
+    pub extension_dependencies: Vec<String>,
+    /// Cargo features to enable when building the Rust library (comma separated)
+    #[arg(
+        long,
+        value_name = "FEATURES",
+        value_delimiter = ',',
+        conflicts_with = "no_build"
+    )]
+    pub features: Vec<String>,
+    /// Registry to resolve versions from: a web API URL or sparse+<index URL>
+    #[arg(long, value_name = "URL")]
+    pub registry: Option<String>,
+    /// Pin the godot crate to a version or requirement (e.g. 0.4.1 or 0.4)
+    #[arg(long, value_name = "VERSION")]
+    pub godot_crate_version: Option<VersionPin>,
+    /// Pin the bevy_ecs crate to a version or requirement
+    #[arg(long, value_name = "VERSION")]
+    pub bevy_ecs_version: Option<VersionPin>,
+    /// Pin any template dependency to a version or requirement (repeatable)
+    #[arg(long = "crate-version", value_name = "CRATE=VERSION", value_parser = parse_crate_version)]
+    pub crate_versions: Vec<(String, VersionPin)>,
+}
+
+impl ProjectArgs {
+    /// Collects every version pin passed on the command line
+    pub fn version_pins(&self) -> BTreeMap<String, VersionPin> {
+        let named = [
+            ("godot", &self.godot_crate_version),
+            ("bevy_ecs", &self.bevy_ecs_version),
+        ];
+
+        named
+            .into_iter()
+            .filter_map(|(crate_name, pin)| Some((crate_name.to_string(), pin.clone()?)))
+            .chain(self.crate_versions.iter().cloned())
+            .collect()
+    }
+
+    /// What the generated `.gdextension` lists
+    pub fn extension_entries(&self) -> ExtensionEntries {
+        ExtensionEntries::new(
+            &self.platforms,
+            self.extension_icons.clone(),
+            self.extension_dependencies.clone(),
+        )
+    }
+
+    /// How to build the library once generated, `None` with `--no-build`
+    pub fn build_options(&self) -> Option<BuildOptions> {
+        (!self.no_build).then(|| BuildOptions {
+            release: self.release,
+            target: self.target.clone(),
+            features: self
+                .features
+                .iter()
+                .map(|feature| feature.trim())
+                .filter(|feature| !feature.is_empty())
+                .map(str::to_string)
+                .collect(),
+        })
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct InitArgs {
+    #[command(flatten)]
+    pub project: ProjectArgs,
+    /// Existing directory to generate the project into, or a directory with a project.godot
+    #[arg(default_value = ".")]
+    pub path: PathBuf,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ListCommand {
+    /// List the templates that can be used with `new` and `init`
+    Templates,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AddCommand {
+    /// Add a Rust class registered with Godot (#[derive(GodotClass)])
+    Class(ClassArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct ClassArgs {
+    /// Class name in any casing, e.g. Player or player_ship
+    pub name: String,
+    /// Godot class to inherit from (e.g. Node, Node2D, Node3D, CharacterBody2D, Control, Resource)
+    #[arg(short, long, default_value = "Node", value_parser = parse_godot_class)]
+    pub base: String,
+    /// Module of the new file, e.g. actors::player [default: the class name in snake_case]
+    #[arg(short, long, value_name = "PATH")]
+    pub module: Option<String>,
+    /// Also create a scene in the Godot project with the class as its root node
+    #[arg(long)]
+    pub scene: bool,
+    /// Any directory of the project, used to find the GDExtension crate
+    #[arg(long, value_name = "DIR", default_value = ".")]
+    pub path: PathBuf,
+}
+
+fn parse_variable(raw: &str) -> Result<(String, String), String> {
+    let (key, value) = raw
+        .split_once('=')
+        .ok_or_else(|| format!("expected KEY=VALUE, got '{}'", raw))?;
+
+    if key.is_empty() {
+        return Err(format!("missing variable name in '{}'", raw));
+    }
+
+    Ok((key.to_string(), value.to_string()))
+}
+
+fn parse_tool_name(raw: &str) -> Result<String, String> {
+    let valid = raw.starts_with(|c: char| c.is_ascii_lowercase())
+        && raw
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+
+    if !valid {
+        return Err(format!(
+            "'{}' is not a snake_case crate name (e.g. level_editor)",
+            raw
+        ));
+    }
+
+    Ok(raw.to_string())
+}
+
+fn parse_godot_class(raw: &str) -> Result<String, String> {
+    let valid = raw.starts_with(|c: char| c.is_ascii_uppercase())
+        && raw.chars().all(|c| c.is_ascii_alphanumeric());
+
+    if !valid {
+        return Err(format!("'{}' is not a Godot class name (e.g. Node2D)", raw));
+    }
+
+    Ok(raw.to_string())
+}
+
+fn parse_extension_icon(raw: &str) -> Result<(String, String), String> {
+    let (class, path) = raw
+        .split_once('=')
+        .ok_or_else(|| format!("expected CLASS=PATH, got '{}'", raw))?;
+    if path.is_empty() {
+        return Err(format!("missing icon path in '{}'", raw));
+    }
+
+    // Relative paths are inside the Godot project
+    let path = match path.starts_with("res://") {
+        true => path.to_string(),
+        false => format!("res://{}", path.trim_start_matches("./")),
+    };
+
+    Ok((parse_godot_class(class)?, path))
+}
+
+fn parse_crate_version(raw: &str) -> Result<(String, VersionPin), String> {
+    let (crate_name, version) = raw
+        .split_once('=')
+        .ok_or_else(|| format!("expected CRATE=VERSION, got '{}'", raw))?;
+
+    if crate_name.is_empty() {
+        return Err(format!("missing crate name in '{}'", raw));
+    }
+
+    Ok((crate_name.to_string(), version.parse()?))
+}
@@ -0,0 +1,532 @@
+/// Subcommand implementations
+///
+/// Each module in this file corresponds to a subcommand declared in
+/// `cli.rs`, except `wizard`, which prompts for missing project options.
+/// The helpers below are shared by the commands that generate project
+/// files (`new` and `init`).
+pub mod add;
+pub mod init;
+pub mod list;
+pub mod new;
+pub mod wizard;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tera::Context;
+
+use crate::error::GodustError;
+use crate::generators::{self, GeneratorInput, PostGenerationStep, Template};
+use crate::utils::cargo::{self, BuildOptions};
+use crate::utils::config::Config;
+use crate::utils::crates_version::VersionPin;
+use crate::utils::engine::{EngineVersion, RenderingMethod};
+use crate::utils::file_plan::{self, FileOrigin, PlannedFile};
+use crate::utils::gdextension::ExtensionEntries;
+use crate::utils::godot_project;
+use crate::utils::layout::{GAME_LOGIC_CRATE, ProjectLayout};
+use crate::utils::name_validation::check_project_name;
+use crate::utils::project::{self, Project};
+use crate::utils::registry::Registry;
+use crate::utils::staging::{self, Staging};
+use crate::utils::template_parser::{self, TemplateSource};
+use crate::utils::uid_generator::UidGenerator;
+
+/// Project options once every required value is known
+///
+/// Built from the command line by [`wizard::complete`].
+#[derive(Debug, Clone)]
+pub struct ProjectOptions {
+    pub name: String,
+    pub engine: EngineVersion,
+    pub rendering_method: RenderingMethod,
+    pub template: String,
+    pub template_path: Option<PathBuf>,
+    pub variables: Vec<(String, String)>,
+    pub layout: ProjectLayout,
+    /// Tool crates added to a workspace layout
+    pub tools: Vec<String>,
+    /// Directory patterns passed with `--godot-dir` and `--core-dir`
+    pub godot_dir: Option<String>,
+    pub core_dir: Option<String>,
+    pub seed: Option<u64>,
+    pub offline: bool,
+    /// Print the files that would be generated instead of writing them
+    pub dry_run: bool,
+    /// Registry URL passed with `--registry`
+    pub registry: Option<String>,
+    /// Versions pinned on the command line, by crate name
+    pub version_pins: BTreeMap<String, VersionPin>,
+    /// How to build the library, `None` with `--no-build`
+    pub build: Option<BuildOptions>,
+    /// Libraries, icons and dependencies listed in the `.gdextension`
+    pub extension: ExtensionEntries,
+}
+
+impl ProjectOptions {
+    /// Seeded UIDs when `--seed` was passed, random ones otherwise
+    pub fn uid_generator(&self) -> UidGenerator {
+        self.seed.map_or(UidGenerator::Random, UidGenerator::Seeded)
+    }
+}
+
+/// Validates the project name and template
+///
+/// The project name may use any casing; it is replaced by its snake_case
+/// form. It must not clash with Rust keywords, reserved crate names, the
+/// template's dependencies or the crates added by the layout. Returns the
+/// resolved template when everything is valid.
+pub fn validate_project_args(args: &mut ProjectOptions) -> Result<Template, GodustError> {
+    // Validate template exists
+    if let Some(path) = &args.template_path
+        && !path.is_dir()
+    {
+        return Err(GodustError::Usage(format!(
+            "Template directory '{}' does not exist.",
+            path.display()
+        )));
+    }
+
+    let template =
+        generators::resolve(&args.template, args.template_path.as_deref())?.ok_or_else(|| {
+            GodustError::UnknownTemplate {
+                name: args.template.clone(),
+                available: generators::template_names(),
+            }
+        })?;
+
+    validate_tools(args)?;
+
+    // Derive the crate, folder and struct names from the project name
+    let extra_crates = args.layout.extra_crates(&args.tools);
+    let dependencies: Vec<&str> = template
+        .generator
+        .manifest()
+        .into_iter()
+        .flat_map(|manifest| manifest.dependencies.keys())
+        .chain(&extra_crates)
+        .map(|c| c.as_str())
+        .collect();
+    let report = check_project_name(&args.name, &dependencies);
+
+    let names = match &report.names {
+        Some(names) if !report.has_errors() => names.clone(),
+        _ => {
+            return Err(GodustError::InvalidName {
+                name: args.name.clone(),
+                reasons: report.errors().map(|problem| problem.message()).collect(),
+                suggestion: report.suggestion.clone(),
+            });
+        }
+    };
+
+    println!("📛 Project names:");
+    println!("   crate:  {}", names.snake);
+    println!("   folder: {}", names.kebab);
+    println!("   struct: {}", names.pascal);
+    for warning in report.warnings() {
+        eprintln!("⚠️  Warning: {}", warning.message());
+    }
+    args.name = names.snake;
+
+    Ok(template)
+}
+
+/// Checks the `--tool` crates fit the layout and don't clash with each other
+fn validate_tools(args: &ProjectOptions) -> Result<(), GodustError> {
+    if args.tools.is_empty() {
+        return Ok(());
+    }
+
+    if args.layout != ProjectLayout::Workspace {
+        return Err(GodustError::Usage(
+            "--tool requires --layout workspace.".to_string(),
+        ));
+    }
+
+    for (index, tool) in args.tools.iter().enumerate() {
+        if tool == GAME_LOGIC_CRATE || args.tools[..index].contains(tool) {
+            return Err(GodustError::Usage(format!(
+                "The workspace already has a crate named '{}'.",
+                tool
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Collects the values every generator needs from the command line
+///
+/// Version pins from the config file apply unless the command line pins the
+/// same crate; `--registry`, `--godot-dir` and `--core-dir` override the
+/// configured values.
+pub fn generator_input(
+    args: &ProjectOptions,
+    template: &Template,
+) -> Result<GeneratorInput, GodustError> {
+    let config = Config::load()?;
+    let mut version_pins = config.version_pins()?;
+
+    let project = Project::new(
+        args.godot_dir
+            .as_deref()
+            .or(config.layout.godot_dir.as_deref()),
+        args.core_dir
+            .as_deref()
+            .or(config.layout.core_dir.as_deref()),
+    );
+    let paths = project
+        .paths(&args.name, args.layout)
+        .map_err(GodustError::Usage)?;
+
+    let registry = Registry::select(args.registry.as_deref(), config.registry.as_deref())
+        .map_err(GodustError::Usage)?;
+    if registry != Registry::default() {
+        println!("🌐 Resolving versions from {}", registry);
+    }
+
+    let dependencies = template.generator.manifest().map(|m| &m.dependencies);
+    for (crate_name, pin) in &args.version_pins {
+        if !dependencies.is_some_and(|deps| deps.contains_key(crate_name)) {
+            eprintln!(
+                "⚠️  Warning: '{}' is not a dependency of the '{}' template, ignoring its version pin.",
+                crate_name, template.name
+            );
+        }
+        version_pins.insert(crate_name.clone(), pin.clone());
+    }
+
+    Ok(GeneratorInput {
+        project_name: args.name.clone(),
+        engine_version: args.engine.clone(),
+        rendering_method: args.rendering_method,
+        paths,
+        tools: args.tools.clone(),
+        offline: args.offline,
+        registry,
+        version_pins,
+        variables: args.variables.iter().cloned().collect(),
+        extension: args.extension.clone(),
+    })
+}
+
+/// Which template files [`render_project`] writes into the Godot project
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GodotFiles {
+    /// Every file, for a new Godot project
+    All,
+    /// Only `.gdextension` files, leaving an existing project's scenes and
+    /// settings alone
+    ExtensionsOnly,
+}
+
+/// Renders every file `template` generates in memory
+///
+/// Paths are relative to the project root: the Godot project, the Rust core
+/// crate, the workspace files of the layout and the `.gdignore` files that
+/// keep Godot out of the Rust directories. Nothing is written.
+pub async fn plan_project(
+    template: &Template,
+    input: &GeneratorInput,
+    uids: UidGenerator,
+    godot_files: GodotFiles,
+) -> Result<Vec<PlannedFile>, GodustError> {
+    println!("🔨 Building template context...");
+    let context = generators::build_checked_context(template.generator.as_ref(), input).await?;
+
+    // Render all template files
+    println!(
+        "📝 Generating project files from '{}' template...",
+        template.name
+    );
+    let include = |path: &Path| {
+        godot_files == GodotFiles::All
+            || !input.paths.is_godot_file(path, &input.tools)
+            || path.extension().is_some_and(|ext| ext == "gdextension")
+    };
+    let mut files =
+        template_parser::plan_template_filtered(&template.source, &context, uids, include)?;
+
+    if input.paths.layout == ProjectLayout::Workspace {
+        files.extend(plan_workspace(input, context, uids)?);
+    }
+
+    // Keep Godot from importing Rust sources and build output
+    for dir in input.paths.ignored_by_godot(&input.tools) {
+        files.push(PlannedFile {
+            path: Path::new(&dir).join(".gdignore"),
+            contents: Vec::new(),
+            origin: FileOrigin::Static,
+        });
+    }
+
+    Ok(files)
+}
+
+/// Renders `template` into `root`
+///
+/// The Godot project and the Rust core crate are created inside `root`,
+/// which is created when missing, along with the workspace files of the
+/// layout. Every file is first written to a staging directory and only
+/// moved into place once all of them were; on an error or Ctrl-C nothing is
+/// left behind. Nothing is written if a file already exists. Returns the
+/// files that were written.
+pub async fn render_project(
+    template: &Template,
+    input: &GeneratorInput,
+    root: &Path,
+    uids: UidGenerator,
+    godot_files: GodotFiles,
+) -> Result<Vec<PathBuf>, GodustError> {
+    let files = plan_project(template, input, uids, godot_files).await?;
+
+    let conflicts = template_parser::conflicts(root, &files);
+    if !conflicts.is_empty() {
+        return Err(GodustError::DestinationConflict(conflicts));
+    }
+
+    staging::catch_interrupts();
+    if root.exists() {
+        let staging = Staging::inside(root)?;
+        staging.write(&files)?;
+        staging.commit_into(root)?;
+    } else {
+        let staging = Staging::next_to(root)?;
+        input.paths.generate_structure(staging.path())?;
+        staging.write(&files)?;
+        staging.commit_as(root)?;
+    }
+
+    Ok(files.iter().map(|file| root.join(&file.path)).collect())
+}
+
+/// Renders the workspace manifest, the `game_logic` crate and every tool crate
+fn plan_workspace(
+    input: &GeneratorInput,
+    context: Context,
+    uids: UidGenerator,
+) -> Result<Vec<PlannedFile>, GodustError> {
+    println!("🧩 Adding the workspace crates...");
+    let layout_dir = |name: &str| {
+        crate::LAYOUTS
+            .get_dir(name)
+            .map(TemplateSource::Embedded)
+            .ok_or_else(|| {
+                GodustError::InvalidTemplate(format!("Layout files '{}' are not embedded", name))
+            })
+    };
+
+    let mut files = template_parser::plan_template(&layout_dir("workspace")?, &context, uids)?;
+
+    let tool_source = layout_dir("tool")?;
+    for tool in &input.tools {
+        let mut tool_context = context.clone();
+        tool_context.insert("tool_name", tool);
+        files.extend(template_parser::plan_template(
+            &tool_source,
+            &tool_context,
+            uids,
+        )?);
+    }
+
+    Ok(files)
+}
+
+/// Prints the files a dry run would create under `root`
+///
+/// Paths already taken are reported as a conflict, just like a real run
+/// would.
+pub fn print_plan(root: &Path, files: &[PlannedFile]) -> Result<(), GodustError> {
+    let rendered = files
+        .iter()
+        .filter(|file| file.origin == FileOrigin::Rendered)
+        .count();
+    let size: usize = files.iter().map(|file| file.contents.len()).sum();
+
+    println!(
+        "\n📋 Dry run: {} files would be created ({} rendered, {} static, {} in total):",
+        files.len(),
+        rendered,
+        files.len() - rendered,
+        file_plan::format_size(size)
+    );
+    println!("   {}/", root.display());
+    for line in file_plan::tree_lines(files) {
+        println!("   {}", line);
+    }
+
+    let conflicts = template_parser::conflicts(root, files);
+    if !conflicts.is_empty() {
+        return Err(GodustError::DestinationConflict(conflicts));
+    }
+
+    println!("\n✅ Nothing was written and cargo build was not run.");
+    Ok(())
+}
+
+/// Prints the generated directories under `root` and how to build them
+pub fn print_structure(root: &str, input: &GeneratorInput) {
+    let paths = &input.paths;
+    let dir = |path: &str| match path {
+        "" => "./".to_string(),
+        path => format!("{}/", path),
+    };
+
+    let mut entries = Vec::new();
+    if paths.layout == ProjectLayout::Workspace {
+        entries.push(("Cargo.toml".to_string(), "Cargo workspace"));
+    }
+    entries.push((dir(&paths.godot_dir), "Godot project"));
+    entries.push((dir(&paths.core_dir), "GDExtension library"));
+    if paths.layout == ProjectLayout::Workspace {
+        let mut members = paths.workspace_members(&input.tools).into_iter().skip(1);
+        if let Some(game_logic) = members.next() {
+            entries.push((dir(&game_logic), "Rust library"));
+        }
+        entries.extend(members.map(|tool| (dir(&tool), "Tool")));
+    }
+
+    let width = entries
+        .iter()
+        .map(|(path, _)| path.len())
+        .max()
+        .unwrap_or(0);
+
+    println!("\n📁 Project structure:");
+    println!("   {}/", root);
+    for (index, (path, description)) in entries.iter().enumerate() {
+        let branch = if index + 1 == entries.len() {
+            "└──"
+        } else {
+            "├──"
+        };
+        println!("   {} {:width$}  ({})", branch, path, description);
+    }
+}
+
+/// Prints the commands to build the project and open it in Godot
+///
+/// Paths are relative to the project root.
+pub fn print_next_steps(input: &GeneratorInput) {
+    let build_dir = input.paths.build_dir();
+    match build_dir.to_str() {
+        Some("") | None => println!("   cargo build"),
+        Some(dir) => println!("   cd {} && cargo build", dir),
+    }
+    println!(
+        "   Open {} in Godot Editor",
+        project::display_dir(&input.paths.godot_dir)
+    );
+}
+
+/// Runs the template's post-generation steps
+///
+/// Every step runs even if an earlier one failed; the first failure is
+/// returned once they are done, after the project itself was generated.
+/// With `--no-build` (`build` is `None`) the cargo builds are skipped.
+pub async fn run_post_generation_steps(
+    template: &Template,
+    input: &GeneratorInput,
+    root: &Path,
+    build: Option<&BuildOptions>,
+) -> Result<(), GodustError> {
+    let mut result = Ok(());
+    for step in template.generator.post_generation_steps(input) {
+        let outcome = match (step, build) {
+            (PostGenerationStep::CargoBuild(_), None) => {
+                println!("⏭️  Skipping the Rust build (--no-build)");
+                Ok(())
+            }
+            (PostGenerationStep::CargoBuild(dir), Some(options)) => {
+                build_crate(root, &root.join(dir), input, options).await
+            }
+        };
+        result = result.and(outcome);
+    }
+
+    // Ctrl-C during the build also stops cargo
+    if staging::interrupted() {
+        return Err(GodustError::Interrupted);
+    }
+    result
+}
+
+/// Builds a generated Rust crate and checks Godot will load the library
+async fn build_crate(
+    root: &Path,
+    crate_dir: &Path,
+    input: &GeneratorInput,
+    options: &BuildOptions,
+) -> Result<(), GodustError> {
+    println!("🔨 Building Rust library (this may take a moment)...");
+    let libraries = cargo::build(crate_dir, options).await?;
+    println!("✅ Rust library built successfully!");
+
+    check_extension_libraries(&root.join(&input.paths.godot_dir), options, &libraries);
+    Ok(())
+}
+
+/// Warns when a `.gdextension` of the Godot project doesn't point at the
+/// library cargo just built for this platform
+fn check_extension_libraries(godot_dir: &Path, options: &BuildOptions, built: &[PathBuf]) {
+    let Some(features) = options.godot_features() else {
+        return;
+    };
+    let built: Vec<PathBuf> = built
+        .iter()
+        .filter_map(|path| path.canonicalize().ok())
+        .collect();
+    if built.is_empty() {
+        return;
+    }
+
+    for extension in godot_project::find_extensions(godot_dir) {
+        let name = extension.strip_prefix(godot_dir).unwrap_or(&extension);
+        let Ok(contents) = fs::read_to_string(&extension) else {
+            continue;
+        };
+        let libraries = match godot_project::extension_libraries(&contents) {
+            Ok(libraries) => libraries,
+            Err(e) => {
+                eprintln!("⚠️  Warning: could not parse {}: {}", name.display(), e);
+                continue;
+            }
+        };
+        let Some(library) = godot_project::library_for(&libraries, &features) else {
+            eprintln!(
+                "⚠️  Warning: {} has no library for {}; Godot won't load the extension there.",
+                name.display(),
+                features.join(".")
+            );
+            continue;
+        };
+
+        // `res://` is the Godot project, other paths are relative to the file
+        let path = match library.strip_prefix("res://") {
+            Some(relative) => godot_dir.join(relative),
+            None => extension.parent().unwrap_or(godot_dir).join(library),
+        };
+        if path.canonicalize().is_ok_and(|path| built.contains(&path)) {
+            println!(
+                "🔗 {} loads the library that was just built",
+                name.display()
+            );
+            continue;
+        }
+
+        eprintln!(
+            "⚠️  Warning: {} loads {} on {}, but cargo built:",
+            name.display(),
+            library,
+            features.join(".")
+        );
+        for path in &built {
+            eprintln!("   {}", path.display());
+        }
+        eprintln!(
+            "   Update the path in {} or Godot won't find the library.",
+            name.display()
+        );
+    }
+}
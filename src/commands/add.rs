@@ -0,0 +1,14 @@
+mod class;
+
+use crate::cli::AddCommand;
+use crate::error::GodustError;
+
+/// Runs `godust add`
+///
+/// Every `add` item is a subcommand of its own, implemented in a module of
+/// the same name.
+pub fn run(item: AddCommand) -> Result<(), GodustError> {
+    match item {
+        AddCommand::Class(args) => class::run(args),
+    }
+}
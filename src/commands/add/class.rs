@@ -0,0 +1,419 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tera::Context;
+use walkdir::WalkDir;
+
+use crate::cli::ClassArgs;
+use crate::error::GodustError;
+use crate::utils::godot_project;
+use crate::utils::name_validation::{check_class_name, check_module_path};
+use crate::utils::template_parser::{self, TemplateSource};
+use crate::utils::uid_generator::UidGenerator;
+
+/// Common base classes that are nodes, and so can be the root of a scene
+const NODE_CLASSES: &[&str] = &[
+    "Node",
+    "Node2D",
+    "Node3D",
+    "CanvasItem",
+    "CanvasLayer",
+    "Control",
+    "Container",
+    "Panel",
+    "Label",
+    "Button",
+    "Area2D",
+    "Area3D",
+    "CharacterBody2D",
+    "CharacterBody3D",
+    "RigidBody2D",
+    "RigidBody3D",
+    "StaticBody2D",
+    "StaticBody3D",
+    "Sprite2D",
+    "Sprite3D",
+    "AnimatedSprite2D",
+    "MeshInstance3D",
+    "Camera2D",
+    "Camera3D",
+    "TileMapLayer",
+    "Timer",
+    "AnimationPlayer",
+    "AudioStreamPlayer",
+    "AudioStreamPlayer2D",
+    "AudioStreamPlayer3D",
+];
+
+/// Common base classes that aren't nodes
+const OTHER_CLASSES: &[&str] = &["Object", "RefCounted", "Resource"];
+
+/// Runs `godust add class`
+///
+/// Writes the class to its own module in the GDExtension crate found from
+/// `--path`, declares the module in `lib.rs` (and in any parent module, which
+/// is created when missing) and, with `--scene`, adds a scene using the class
+/// to the Godot project. Everything is rendered before anything is written,
+/// and nothing is left behind if a file already exists or a write fails.
+pub fn run(args: ClassArgs) -> Result<(), GodustError> {
+    let names = check_class_name(&args.name).map_err(|reason| GodustError::InvalidName {
+        name: args.name.clone(),
+        reasons: vec![reason],
+        suggestion: None,
+    })?;
+    let module = check_module_path(args.module.as_deref().unwrap_or(&names.snake))
+        .map_err(GodustError::Usage)?;
+
+    let base = args.base.as_str();
+    let is_node = !OTHER_CLASSES.contains(&base);
+    if is_node && !NODE_CLASSES.contains(&base) {
+        eprintln!(
+            "⚠️  Warning: '{}' isn't a base class godust knows; make sure Godot has it.",
+            base
+        );
+    }
+    if args.scene && !is_node {
+        return Err(GodustError::Usage(format!(
+            "--scene needs a node class, but '{}' isn't a node.",
+            base
+        )));
+    }
+
+    let Some(crate_dir) = find_extension_crate(&args.path) else {
+        return Err(GodustError::Usage(format!(
+            "No GDExtension crate (a src/lib.rs with #[gdextension]) found in or around '{}'.\nRun godust add inside a project or point --path at its crate.",
+            args.path.display()
+        )));
+    };
+    let godot_dir = if args.scene {
+        let Some(dir) = godot_project::find_near(&crate_dir) else {
+            return Err(GodustError::Usage(format!(
+                "No Godot project found near '{}' for the scene.",
+                crate_dir.display()
+            )));
+        };
+        Some(dir)
+    } else {
+        None
+    };
+
+    let class_file = format!("src/{}.rs", module.join("/"));
+    let scene_file = format!("{}.tscn", names.snake);
+    let mut planned = vec![crate_dir.join(&class_file)];
+    planned.extend(godot_dir.iter().map(|dir| dir.join(&scene_file)));
+    let existing: Vec<PathBuf> = planned.into_iter().filter(|path| path.exists()).collect();
+    if !existing.is_empty() {
+        return Err(GodustError::DestinationConflict(existing));
+    }
+
+    let mut context = Context::new();
+    context.insert("class_name", &names.pascal);
+    context.insert("base_class", base);
+    context.insert("class_imports", &class_imports(base));
+    context.insert("class_file", &class_file);
+    context.insert("scene_file", &scene_file);
+
+    println!(
+        "🧩 Adding class '{}' ({}) to {}",
+        names.pascal,
+        base,
+        crate_dir.display()
+    );
+    let mut writes = plan_item("class", &crate_dir, &context)?;
+    let declarations = declare_modules(&crate_dir.join("src"), &module)?;
+    writes.extend(declarations.iter().map(|(_, _, write)| write.clone()));
+    if let Some(godot_dir) = &godot_dir {
+        writes.extend(plan_item("scene", godot_dir, &context)?);
+    }
+    write_all(&writes)?;
+
+    println!("   created {}", class_file);
+    for (file, declared, _) in &declarations {
+        println!("   declared mod {} in src/{}", declared, file);
+    }
+    if let Some(godot_dir) = &godot_dir {
+        println!("   created {}", godot_dir.join(&scene_file).display());
+    }
+
+    println!("\n✅ Added '{}'!", names.pascal);
+    println!("\n💡 Next steps:");
+    println!("   cd {} && cargo build", crate_dir.display());
+    if godot_dir.is_some() {
+        println!("   Open res://{} in Godot Editor", scene_file);
+    }
+
+    Ok(())
+}
+
+/// A file `add class` creates or changes
+#[derive(Debug, Clone)]
+struct FileWrite {
+    path: PathBuf,
+    contents: Vec<u8>,
+    /// What the file held before, `None` when it is created
+    previous: Option<Vec<u8>>,
+}
+
+impl FileWrite {
+    /// Writes the file, recording the directories created for it
+    fn apply(&self, created_dirs: &mut Vec<PathBuf>) -> io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            let missing: Vec<PathBuf> = parent
+                .ancestors()
+                .take_while(|dir| !dir.as_os_str().is_empty() && !dir.exists())
+                .map(Path::to_path_buf)
+                .collect();
+            created_dirs.extend(missing.into_iter().rev());
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, &self.contents)
+    }
+
+    /// Puts back what was there before [`Self::apply`]
+    fn undo(&self) {
+        let _ = match &self.previous {
+            Some(previous) => fs::write(&self.path, previous),
+            None => fs::remove_file(&self.path),
+        };
+    }
+}
+
+/// Writes every file in order, or none of them
+///
+/// When a write fails, the files already written are restored or removed
+/// along with the directories created for them.
+fn write_all(writes: &[FileWrite]) -> Result<(), GodustError> {
+    let mut created_dirs = Vec::new();
+
+    for (index, write) in writes.iter().enumerate() {
+        if let Err(e) = write.apply(&mut created_dirs) {
+            for written in writes[..=index].iter().rev() {
+                written.undo();
+            }
+            for dir in created_dirs.iter().rev() {
+                let _ = fs::remove_dir(dir);
+            }
+            return Err(e.into());
+        }
+    }
+
+    Ok(())
+}
+
+/// Renders one of the embedded `godust add` items in memory, as new files
+/// in `dest`
+fn plan_item(name: &str, dest: &Path, context: &Context) -> Result<Vec<FileWrite>, GodustError> {
+    let source = crate::ITEMS
+        .get_dir(name)
+        .map(TemplateSource::Embedded)
+        .ok_or_else(|| {
+            GodustError::InvalidTemplate(format!("Item files '{}' are not embedded", name))
+        })?;
+
+    let files = template_parser::plan_template(&source, context, UidGenerator::Random)?;
+    Ok(files
+        .into_iter()
+        .map(|file| FileWrite {
+            path: dest.join(file.path),
+            contents: file.contents,
+            previous: None,
+        })
+        .collect())
+}
+
+/// `{INode2D, Node2D}`: the base class and its virtual methods trait, in
+/// the order rustfmt sorts them
+fn class_imports(base: &str) -> String {
+    let mut imports = [base.to_string(), format!("I{}", base)];
+    imports.sort();
+    format!("{{{}}}", imports.join(", "))
+}
+
+/// Finds the GDExtension crate: the nearest directory with a `src/lib.rs`
+/// containing `#[gdextension]`, among `start`, its parents and then its
+/// subdirectories
+fn find_extension_crate(start: &Path) -> Option<PathBuf> {
+    let start = start.canonicalize().ok()?;
+    let is_extension_crate = |dir: &Path| {
+        fs::read_to_string(dir.join("src").join("lib.rs"))
+            .is_ok_and(|lib| lib.contains("#[gdextension]"))
+    };
+
+    if let Some(dir) = start.ancestors().find(|dir| is_extension_crate(dir)) {
+        return Some(dir.to_path_buf());
+    }
+
+    WalkDir::new(&start)
+        .min_depth(1)
+        .max_depth(3)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            entry.file_type().is_dir() && !name.starts_with('.') && name != "target"
+        })
+        .filter_map(Result::ok)
+        .find(|entry| is_extension_crate(entry.path()))
+        .map(|entry| entry.into_path())
+}
+
+/// Declares every segment of `module` in its parent, from `lib.rs` down
+///
+/// Parent modules use `<name>.rs` unless a `<name>/mod.rs` already exists,
+/// and are created when missing. Nothing is written: returns the files to
+/// change (relative to `src_dir`) with the module declared in each.
+fn declare_modules(
+    src_dir: &Path,
+    module: &[String],
+) -> io::Result<Vec<(String, String, FileWrite)>> {
+    let mut changed = Vec::new();
+
+    for (depth, name) in module.iter().enumerate() {
+        let parent = &module[..depth];
+        let file = match parent {
+            [] => "lib.rs".to_string(),
+            _ if src_dir.join(parent.join("/")).join("mod.rs").is_file() => {
+                format!("{}/mod.rs", parent.join("/"))
+            }
+            _ => format!("{}.rs", parent.join("/")),
+        };
+
+        let path = src_dir.join(&file);
+        let previous = match fs::read_to_string(&path) {
+            Ok(source) => Some(source),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e),
+        };
+
+        if let Some(updated) = declare_module(previous.as_deref().unwrap_or_default(), name) {
+            let write = FileWrite {
+                path,
+                contents: updated.into_bytes(),
+                previous: previous.map(String::into_bytes),
+            };
+            changed.push((file, name.clone(), write));
+        }
+    }
+
+    Ok(changed)
+}
+
+/// Adds `mod <name>;` to a module's source, after its other `mod`
+/// declarations or else after its `use` declarations
+///
+/// Returns `None` when the module is already declared.
+fn declare_module(source: &str, name: &str) -> Option<String> {
+    let declaration = format!("mod {};", name);
+    let is_mod = |line: &str| {
+        let line = line
+            .strip_prefix("pub(crate) ")
+            .or_else(|| line.strip_prefix("pub "))
+            .unwrap_or(line);
+        line.starts_with("mod ") && line.ends_with(';')
+    };
+
+    let mut lines: Vec<&str> = source.lines().collect();
+    if lines
+        .iter()
+        .any(|line| is_mod(line.trim()) && line.trim().ends_with(&format!(" {};", name)))
+    {
+        return None;
+    }
+
+    if let Some(last_mod) = lines.iter().rposition(|line| is_mod(line)) {
+        lines.insert(last_mod + 1, &declaration);
+    } else if let Some(last_use) = lines
+        .iter()
+        .rposition(|line| line.starts_with("use ") || line.starts_with("pub use "))
+    {
+        // A `use` may span several lines
+        let end = (last_use..lines.len())
+            .find(|&index| lines[index].trim_end().ends_with(';'))
+            .unwrap_or(last_use);
+        let followed_by_code = lines.get(end + 1).is_some_and(|line| !line.is_empty());
+        lines.splice(end + 1..end + 1, ["", declaration.as_str()]);
+        if followed_by_code {
+            lines.insert(end + 3, "");
+        }
+    } else {
+        if !lines.is_empty() {
+            lines.insert(0, "");
+        }
+        lines.insert(0, &declaration);
+    }
+
+    Some(lines.join("\n") + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn declares_modules_next_to_existing_ones() {
+        let lib = "use godot::prelude::*;\n\nstruct MyGame;\n";
+        assert_eq!(
+            declare_module(lib, "player").unwrap(),
+            "use godot::prelude::*;\n\nmod player;\n\nstruct MyGame;\n"
+        );
+
+        let with_mods = "use godot::prelude::*;\n\nmod examples;\n\nstruct MyGame;\n";
+        assert_eq!(
+            declare_module(with_mods, "player").unwrap(),
+            "use godot::prelude::*;\n\nmod examples;\nmod player;\n\nstruct MyGame;\n"
+        );
+        assert_eq!(declare_module(with_mods, "examples"), None);
+
+        assert_eq!(declare_module("", "player").unwrap(), "mod player;\n");
+    }
+
+    #[test]
+    fn imports_are_sorted_like_rustfmt() {
+        assert_eq!(class_imports("Node2D"), "{INode2D, Node2D}");
+        assert_eq!(class_imports("Control"), "{Control, IControl}");
+    }
+
+    #[test]
+    fn failed_write_puts_everything_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src");
+        fs::create_dir(&src).unwrap();
+        fs::write(src.join("lib.rs"), "struct MyGame;\n").unwrap();
+        // A file where the scene's directory should be
+        fs::write(dir.path().join("game"), "").unwrap();
+
+        let module = ["enemies".to_string(), "boss".to_string()];
+        let mut writes = vec![FileWrite {
+            path: src.join("enemies/boss.rs"),
+            contents: b"struct Boss;\n".to_vec(),
+            previous: None,
+        }];
+        let declarations = declare_modules(&src, &module).unwrap();
+        writes.extend(declarations.into_iter().map(|(_, _, write)| write));
+        writes.push(FileWrite {
+            path: dir.path().join("game/boss.tscn"),
+            contents: Vec::new(),
+            previous: None,
+        });
+
+        assert!(write_all(&writes).is_err());
+        assert_eq!(
+            fs::read_to_string(src.join("lib.rs")).unwrap(),
+            "struct MyGame;\n"
+        );
+        assert!(!src.join("enemies").exists());
+        assert!(!src.join("enemies.rs").exists());
+
+        writes.pop();
+        write_all(&writes).unwrap();
+        assert_eq!(
+            fs::read_to_string(src.join("lib.rs")).unwrap(),
+            "mod enemies;\n\nstruct MyGame;\n"
+        );
+        assert_eq!(
+            fs::read_to_string(src.join("enemies.rs")).unwrap(),
+            "mod boss;\n"
+        );
+        assert!(src.join("enemies/boss.rs").is_file());
+    }
+}
@@ -0,0 +1,180 @@
+use std::path::Path;
+
+use crate::cli::{InitArgs, ProjectArgs};
+use crate::commands::{self, GodotFiles, wizard};
+use crate::error::GodustError;
+use crate::utils::godot_project::{self, GodotProject};
+
+/// Runs `godust init`
+///
+/// Generates the selected template directly inside an existing directory
+/// instead of creating a new `<kebab-name>/` root. When the directory is a
+/// Godot project, only the Rust side is added to it instead. Existing files
+/// are never overwritten; any conflict aborts generation before anything is
+/// written.
+pub async fn run(args: InitArgs) -> Result<(), GodustError> {
+    let root = args.path;
+    if GodotProject::exists_in(&root) {
+        return add_to_godot_project(&root, args.project).await;
+    }
+
+    let mut args = wizard::complete(args.project)?;
+    let template = commands::validate_project_args(&mut args)?;
+
+    if !root.is_dir() {
+        return Err(GodustError::Usage(format!(
+            "'{}' is not an existing directory.\nUse 'godust new' to create a project in a new directory.",
+            root.display()
+        )));
+    }
+
+    let input = commands::generator_input(&args, &template)?;
+    if args.dry_run {
+        let files =
+            commands::plan_project(&template, &input, args.uid_generator(), GodotFiles::All)
+                .await?;
+        return commands::print_plan(&root, &files);
+    }
+
+    commands::render_project(
+        &template,
+        &input,
+        &root,
+        args.uid_generator(),
+        GodotFiles::All,
+    )
+    .await?;
+    let build =
+        commands::run_post_generation_steps(&template, &input, &root, args.build.as_ref()).await;
+
+    // Success summary
+    println!(
+        "\n✅ Successfully initialized '{}' from template '{}' in {}!",
+        args.name,
+        template.name,
+        root.display()
+    );
+    commands::print_structure(&root.display().to_string(), &input);
+    println!("\n💡 Next steps:");
+    commands::print_next_steps(&input);
+
+    build
+}
+
+/// Adds a GDExtension crate next to the Godot project in `godot_dir`
+///
+/// The name, engine version and renderer default to the project's own. Of
+/// the template's Godot files only the `.gdextension` is written, and it is
+/// registered in `.godot/extension_list.cfg` so the editor loads it.
+async fn add_to_godot_project(godot_dir: &Path, args: ProjectArgs) -> Result<(), GodustError> {
+    let godot_dir = godot_dir.canonicalize()?;
+    let (Some(root), Some(dir_name)) = (
+        godot_dir.parent(),
+        godot_dir.file_name().and_then(|name| name.to_str()),
+    ) else {
+        return Err(GodustError::Usage(format!(
+            "The Godot project in '{}' has no parent directory to add the crate to.",
+            godot_dir.display()
+        )));
+    };
+
+    if args.godot_dir.is_some() {
+        return Err(GodustError::Usage(format!(
+            "--godot-dir can't be used in an existing Godot project.\nThe crate goes next to '{}'.",
+            godot_dir.display()
+        )));
+    }
+
+    let existing = GodotProject::load(&godot_dir)?;
+    println!(
+        "🎮 Found Godot project '{}' in {}",
+        existing.name.as_deref().unwrap_or(dir_name),
+        godot_dir.display()
+    );
+
+    let args = ProjectArgs {
+        name: args.name.or_else(|| existing.crate_name()),
+        engine: args.engine.or_else(|| existing.engine_version()),
+        rendering_method: args
+            .rendering_method
+            .or_else(|| Some(existing.rendering_method())),
+        godot_dir: Some(dir_name.to_string()),
+        ..args
+    };
+    let mut args = wizard::complete(args)?;
+    let template = commands::validate_project_args(&mut args)?;
+
+    let input = commands::generator_input(&args, &template)?;
+    if args.dry_run {
+        let files = commands::plan_project(
+            &template,
+            &input,
+            args.uid_generator(),
+            GodotFiles::ExtensionsOnly,
+        )
+        .await?;
+        for file in &files {
+            if let Some(res_path) = extension_res_path(&godot_dir, &root.join(&file.path)) {
+                println!(
+                    "🧩 Would register {} in .godot/extension_list.cfg",
+                    res_path
+                );
+            }
+        }
+        return commands::print_plan(root, &files);
+    }
+
+    let written = commands::render_project(
+        &template,
+        &input,
+        root,
+        args.uid_generator(),
+        GodotFiles::ExtensionsOnly,
+    )
+    .await?;
+
+    for res_path in written
+        .iter()
+        .filter_map(|path| extension_res_path(&godot_dir, path))
+    {
+        // The project files are already in place, so a failed registration
+        // only means the editor has to pick the extension up on its own
+        match godot_project::register_extension(&godot_dir, &res_path) {
+            Ok(true) => println!("🧩 Registered {} in .godot/extension_list.cfg", res_path),
+            Ok(false) => {}
+            Err(e) => eprintln!(
+                "⚠️  Warning: Could not register {} in .godot/extension_list.cfg: {}",
+                res_path, e
+            ),
+        }
+    }
+
+    let build =
+        commands::run_post_generation_steps(&template, &input, root, args.build.as_ref()).await;
+
+    // Success summary
+    println!(
+        "\n✅ Successfully added '{}' from template '{}' to the Godot project in {}!",
+        args.name,
+        template.name,
+        godot_dir.display()
+    );
+    commands::print_structure(&root.display().to_string(), &input);
+    println!("\n💡 Next steps:");
+    println!("   cd {}", root.display());
+    commands::print_next_steps(&input);
+
+    build
+}
+
+/// The `res://` path of a `.gdextension` file inside the Godot project
+fn extension_res_path(godot_dir: &Path, path: &Path) -> Option<String> {
+    if path.extension().is_none_or(|ext| ext != "gdextension") {
+        return None;
+    }
+    let relative = path.strip_prefix(godot_dir).ok()?;
+    Some(format!(
+        "res://{}",
+        relative.to_string_lossy().replace('\\', "/")
+    ))
+}
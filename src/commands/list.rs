@@ -0,0 +1,95 @@
+use std::fmt::Write;
+
+use crate::cli::ListCommand;
+use crate::generators::{self, Generator};
+use crate::utils::template_manifest::BUILTIN_VARIABLES;
+
+/// Runs `godust list`
+pub fn run(item: ListCommand) {
+    match item {
+        ListCommand::Templates => print!("{}", templates_overview()),
+    }
+}
+
+/// Describes the built-in templates and the variables they accept, for the
+/// long `--help` output
+///
+/// User templates are left to `godust list templates`, as loading them reads
+/// the user templates directory.
+pub fn builtin_templates_help() -> String {
+    let mut out = builtin_templates();
+    out.push_str("\nRun `godust list templates` to also see the user templates.\n");
+    out
+}
+
+fn builtin_templates() -> String {
+    let mut out = String::from("Available templates:\n");
+    for generator in generators::registry() {
+        describe(&mut out, generator.as_ref());
+    }
+    out
+}
+
+/// Describes every available template and the variables it accepts
+fn templates_overview() -> String {
+    let mut out = builtin_templates();
+
+    let user_templates = generators::user_templates();
+    if !user_templates.is_empty() {
+        out.push_str("\nUser templates:\n");
+        for (name, template) in user_templates {
+            match template {
+                Ok(template) => describe(&mut out, template.generator.as_ref()),
+                Err(e) => {
+                    let _ = writeln!(out, "   {:<12} ⚠️  Could not be loaded: {}", name, e);
+                }
+            }
+        }
+    }
+
+    out
+}
+
+fn describe(out: &mut String, generator: &dyn Generator) {
+    let _ = writeln!(
+        out,
+        "   {:<12} {}",
+        generator.name(),
+        generator.description()
+    );
+
+    if let Some(manifest) = generator.manifest() {
+        let mut details = Vec::new();
+        if let Some(minimum) = &manifest.min_engine_version {
+            details.push(format!("Godot {}+", minimum));
+        }
+        if !manifest.dependencies.is_empty() {
+            let crates: Vec<&str> = manifest.dependencies.keys().map(|c| c.as_str()).collect();
+            details.push(format!("crates: {}", crates.join(", ")));
+        }
+        if !details.is_empty() {
+            let _ = writeln!(out, "   {:<12} ({})", "", details.join("; "));
+        }
+    }
+
+    let builtin = match generator.manifest() {
+        Some(manifest) => manifest.builtin_variables(),
+        None => BUILTIN_VARIABLES.iter().map(|v| v.to_string()).collect(),
+    };
+    let variables = generator
+        .variables()
+        .iter()
+        .filter(|v| !builtin.contains(&v.name));
+
+    for variable in variables {
+        let usage = format!("--var {}=<{}>", variable.name, variable.kind.as_str());
+        let mut line = format!("   {:<12}   {:<32} {}", "", usage, variable.description);
+
+        if let Some(default) = &variable.default {
+            let _ = write!(line, " [default: {}]", default);
+        } else if variable.required {
+            line.push_str(" [required]");
+        }
+        let _ = writeln!(out, "{}", line.trim_end());
+    }
+}
@@ -0,0 +1,54 @@
+use std::path::Path;
+
+use crate::cli::ProjectArgs;
+use crate::commands::{self, GodotFiles, wizard};
+use crate::error::GodustError;
+use crate::utils::naming::{self, Casing};
+
+/// Runs `godust new`
+///
+/// Generates the selected template into a new `<kebab-name>/` directory in
+/// the current working directory, which only appears once every file was
+/// written. With `--dry-run` the files are only listed.
+pub async fn run(args: ProjectArgs) -> Result<(), GodustError> {
+    let mut args = wizard::complete(args)?;
+    let template = commands::validate_project_args(&mut args)?;
+    let input = commands::generator_input(&args, &template)?;
+
+    // The project root is named after the kebab-case name
+    let kebab_name = naming::split_into_kebab_case(&args.name, Casing::SnakeCase);
+    let root = Path::new(&kebab_name);
+    if root.exists() {
+        return Err(GodustError::DestinationConflict(vec![root.to_path_buf()]));
+    }
+
+    if args.dry_run {
+        let files =
+            commands::plan_project(&template, &input, args.uid_generator(), GodotFiles::All)
+                .await?;
+        return commands::print_plan(root, &files);
+    }
+
+    commands::render_project(
+        &template,
+        &input,
+        root,
+        args.uid_generator(),
+        GodotFiles::All,
+    )
+    .await?;
+    let build =
+        commands::run_post_generation_steps(&template, &input, root, args.build.as_ref()).await;
+
+    // Success summary
+    println!(
+        "\n✅ Successfully generated '{}' from template '{}'!",
+        args.name, template.name
+    );
+    commands::print_structure(&kebab_name, &input);
+    println!("\n💡 Next steps:");
+    println!("   cd {}", kebab_name);
+    commands::print_next_steps(&input);
+
+    build
+}
@@ -0,0 +1,165 @@
+use clap::ValueEnum;
+use std::io;
+
+use crate::cli::ProjectArgs;
+use crate::commands::ProjectOptions;
+use crate::error::GodustError;
+use crate::generators;
+use crate::utils::engine::{EngineVersion, KNOWN_ENGINE_VERSIONS, RenderingMethod};
+use crate::utils::name_validation::check_project_name;
+use crate::utils::prompt;
+
+const DEFAULT_TEMPLATE: &str = "blank";
+
+/// What the wizard asks for when it is missing from the command line
+struct Choices {
+    name: String,
+    template: String,
+    engine: EngineVersion,
+    rendering_method: RenderingMethod,
+}
+
+/// Fills in the project options missing from the command line
+///
+/// When every required option was passed nothing is asked. Otherwise the
+/// user is prompted for each missing value if stdin is a terminal; when it
+/// isn't, the missing options are reported as a usage error.
+pub fn complete(args: ProjectArgs) -> Result<ProjectOptions, GodustError> {
+    let choices = match (&args.name, &args.engine, &args.rendering_method) {
+        (Some(name), Some(engine), Some(rendering_method)) => Choices {
+            name: name.clone(),
+            template: args
+                .template
+                .clone()
+                .unwrap_or_else(|| DEFAULT_TEMPLATE.to_string()),
+            engine: engine.clone(),
+            rendering_method: *rendering_method,
+        },
+        _ => prompt_missing(&args)?,
+    };
+
+    Ok(ProjectOptions {
+        name: choices.name,
+        engine: choices.engine,
+        rendering_method: choices.rendering_method,
+        template: choices.template,
+        version_pins: args.version_pins(),
+        build: args.build_options(),
+        extension: args.extension_entries(),
+        template_path: args.template_path,
+        variables: args.variables,
+        layout: args.layout,
+        tools: args.tools,
+        godot_dir: args.godot_dir,
+        core_dir: args.core_dir,
+        seed: args.seed,
+        offline: args.offline,
+        dry_run: args.dry_run,
+        registry: args.registry,
+    })
+}
+
+/// Prompts for the choices missing from `args`, or reports them when stdin
+/// isn't a terminal
+fn prompt_missing(args: &ProjectArgs) -> Result<Choices, GodustError> {
+    if !prompt::is_interactive() {
+        let missing: Vec<&str> = [
+            (args.name.is_none(), "--name"),
+            (args.engine.is_none(), "--engine"),
+            (args.rendering_method.is_none(), "--rendering-method"),
+        ]
+        .into_iter()
+        .filter_map(|(missing, flag)| missing.then_some(flag))
+        .collect();
+
+        return Err(GodustError::Usage(format!(
+            "Missing required options: {}\nPass them on the command line or run godust in a terminal to be prompted.",
+            missing.join(", ")
+        )));
+    }
+
+    println!("🧙 Let's set up your project (press Enter to accept the [default])\n");
+
+    let name = match &args.name {
+        Some(name) => name.clone(),
+        None => prompt::input("Project name", None, validate_name)?,
+    };
+
+    let template = match (&args.template, &args.template_path) {
+        (Some(template), _) => template.clone(),
+        // The template directory decides the files, the name only picks the generator
+        (None, Some(_)) => DEFAULT_TEMPLATE.to_string(),
+        (None, None) => {
+            let names = generators::template_names();
+            let choices: Vec<String> = names
+                .iter()
+                .map(|name| match generators::find(name) {
+                    Some(generator) => format!("{} - {}", name, generator.description()),
+                    None => name.clone(),
+                })
+                .collect();
+            let default = names
+                .iter()
+                .position(|n| n == DEFAULT_TEMPLATE)
+                .unwrap_or(0);
+            names[prompt::select("\nTemplate", &choices, default)?].clone()
+        }
+    };
+
+    let engine = match &args.engine {
+        Some(engine) => engine.clone(),
+        None => {
+            let mut choices: Vec<String> = KNOWN_ENGINE_VERSIONS
+                .iter()
+                .map(|v| v.to_string())
+                .collect();
+            choices.push("Other...".to_string());
+
+            let choice = prompt::select("\nGodot engine version", &choices, 0)?;
+            let version = match KNOWN_ENGINE_VERSIONS.get(choice) {
+                Some(version) => version.to_string(),
+                None => prompt::input("Engine version (e.g. 4.6)", None, validate_engine)?,
+            };
+            version.parse().map_err(io::Error::other)?
+        }
+    };
+
+    let rendering_method = match args.rendering_method {
+        Some(method) => method,
+        None => {
+            let methods = RenderingMethod::value_variants();
+            let choices: Vec<String> = methods
+                .iter()
+                .map(|m| format!("{} ({})", m.display_name(), m))
+                .collect();
+            methods[prompt::select("\nRendering method", &choices, 0)?]
+        }
+    };
+
+    println!();
+
+    Ok(Choices {
+        name,
+        template,
+        engine,
+        rendering_method,
+    })
+}
+
+fn validate_name(name: &str) -> Result<(), String> {
+    // Dependency clashes depend on the template, which is picked later
+    let report = check_project_name(name, &[]);
+    if !report.has_errors() {
+        return Ok(());
+    }
+
+    let mut message: Vec<String> = report.errors().map(|p| p.message()).collect();
+    if let Some(suggestion) = &report.suggestion {
+        message.push(format!("did you mean '{}'?", suggestion));
+    }
+    Err(format!("'{}' can't be used: {}", name, message.join("; ")))
+}
+
+fn validate_engine(version: &str) -> Result<(), String> {
+    version.parse::<EngineVersion>().map(|_| ())
+}
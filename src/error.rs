@@ -0,0 +1,169 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// Why godust stopped, each kind with its own exit code
+///
+/// The codes are part of the command-line interface (scripts rely on them)
+/// and are listed in the README; never renumber a variant.
+#[derive(Debug)]
+pub enum GodustError {
+    /// Any other I/O failure (exit code 1)
+    Io(io::Error),
+    /// Missing or conflicting command-line options (exit code 2, like
+    /// clap's own usage errors)
+    Usage(String),
+    /// The project, class or module name can't be used (exit code 3)
+    InvalidName {
+        name: String,
+        reasons: Vec<String>,
+        suggestion: Option<String>,
+    },
+    /// No template with this name exists (exit code 4)
+    UnknownTemplate {
+        name: String,
+        available: Vec<String>,
+    },
+    /// The template is broken, or doesn't accept the requested engine
+    /// version or variables (exit code 5)
+    InvalidTemplate(String),
+    /// Generation would overwrite these existing paths (exit code 6)
+    DestinationConflict(Vec<PathBuf>),
+    /// A template file or path couldn't be rendered (exit code 7)
+    TemplateRender(String),
+    /// Dependency versions couldn't be resolved from the registry (exit code 8)
+    Network(String),
+    /// The project was generated but `cargo build` failed (exit code 9)
+    Build(String),
+    /// The config file can't be read (exit code 10)
+    Config(String),
+    /// Ctrl-C was pressed; files not yet moved into place were removed
+    /// (exit code 130, as for a command killed by SIGINT)
+    Interrupted,
+}
+
+impl GodustError {
+    /// Process exit code for this error
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            GodustError::Io(_) => 1,
+            GodustError::Usage(_) => 2,
+            GodustError::InvalidName { .. } => 3,
+            GodustError::UnknownTemplate { .. } => 4,
+            GodustError::InvalidTemplate(_) => 5,
+            GodustError::DestinationConflict(_) => 6,
+            GodustError::TemplateRender(_) => 7,
+            GodustError::Network(_) => 8,
+            GodustError::Build(_) => 9,
+            GodustError::Config(_) => 10,
+            GodustError::Interrupted => 130,
+        }
+    }
+}
+
+impl fmt::Display for GodustError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GodustError::Io(e) => write!(f, "{}", e),
+            GodustError::Usage(message)
+            | GodustError::InvalidTemplate(message)
+            | GodustError::TemplateRender(message)
+            | GodustError::Network(message)
+            | GodustError::Build(message)
+            | GodustError::Config(message) => f.write_str(message),
+            GodustError::InvalidName {
+                name,
+                reasons,
+                suggestion,
+            } => {
+                write!(f, "The name '{}' can't be used:", name)?;
+                for reason in reasons {
+                    write!(f, "\n   - {}", reason)?;
+                }
+                if let Some(suggestion) = suggestion {
+                    write!(f, "\nDid you mean '{}'?", suggestion)?;
+                }
+                Ok(())
+            }
+            GodustError::UnknownTemplate { name, available } => write!(
+                f,
+                "Template '{}' is not supported.\nAvailable templates: {}",
+                name,
+                available.join(", ")
+            ),
+            GodustError::Interrupted => f.write_str("Interrupted by Ctrl-C"),
+            GodustError::DestinationConflict(paths) => {
+                f.write_str("Refusing to overwrite what already exists:")?;
+                for path in paths {
+                    write!(f, "\n   - {}", path.display())?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for GodustError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GodustError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for GodustError {
+    fn from(e: io::Error) -> Self {
+        GodustError::Io(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn exit_codes_are_distinct() {
+        let errors = [
+            GodustError::Io(io::Error::other("disk full")),
+            GodustError::Usage(String::new()),
+            GodustError::InvalidName {
+                name: String::new(),
+                reasons: Vec::new(),
+                suggestion: None,
+            },
+            GodustError::UnknownTemplate {
+                name: String::new(),
+                available: Vec::new(),
+            },
+            GodustError::InvalidTemplate(String::new()),
+            GodustError::DestinationConflict(Vec::new()),
+            GodustError::TemplateRender(String::new()),
+            GodustError::Network(String::new()),
+            GodustError::Build(String::new()),
+            GodustError::Config(String::new()),
+            GodustError::Interrupted,
+        ];
+
+        let codes: Vec<u8> = errors.iter().map(GodustError::exit_code).collect();
+        let distinct: HashSet<u8> = codes.iter().copied().collect();
+        assert_eq!(distinct.len(), errors.len());
+        assert_eq!(codes[..10], (1..=10).collect::<Vec<u8>>());
+        assert_eq!(codes[10..], [130]);
+    }
+
+    #[test]
+    fn lists_every_conflict() {
+        let error = GodustError::DestinationConflict(vec![
+            PathBuf::from("demo/project.godot"),
+            PathBuf::from("demo_core/Cargo.toml"),
+        ]);
+
+        assert_eq!(
+            error.to_string(),
+            "Refusing to overwrite what already exists:\n   - demo/project.godot\n   - demo_core/Cargo.toml"
+        );
+    }
+}
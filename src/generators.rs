@@ -1,7 +1,356 @@
-/// Template-specific context builders
+/// Template context builders
 ///
-/// Each module in this file corresponds to a template in src/templates/
-/// and is responsible for building the Tera Context with all variables
-/// required by that template.
-pub mod blank;
-pub mod blank_ecs;
+/// A [`Generator`] builds the Tera Context for a template and lists the
+/// steps to run once its files are written. Every template directory carries
+/// a `template.toml` manifest, and [`manifest::ManifestGenerator`] builds the
+/// context from it generically, so adding a template only requires adding
+/// its directory to src/templates/ (or to the user templates directory).
+pub mod manifest;
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, OnceLock};
+use tera::Context;
+
+use crate::error::GodustError;
+use crate::generators::manifest::ManifestGenerator;
+use crate::utils::crates_version::VersionPin;
+use crate::utils::engine::{EngineVersion, RenderingMethod};
+use crate::utils::gdextension::ExtensionEntries;
+use crate::utils::project::ProjectPaths;
+use crate::utils::registry::Registry;
+use crate::utils::template_manifest::{TemplateManifest, VariableSpec};
+use crate::utils::template_parser::TemplateSource;
+use crate::utils::{naming, paths};
+
+/// Future returned by [`Generator::build_context`]
+pub type ContextFuture<'a> = Pin<Box<dyn Future<Output = Result<Context, GodustError>> + 'a>>;
+
+/// Values provided by the user that every generator receives
+#[derive(Debug, Clone)]
+pub struct GeneratorInput {
+    /// Snake case project name (e.g., "my_game")
+    pub project_name: String,
+    /// Godot engine version (e.g., 4.3)
+    pub engine_version: EngineVersion,
+    pub rendering_method: RenderingMethod,
+    /// Where the Godot project and the crates go
+    pub paths: ProjectPaths,
+    /// Tool crates added to a workspace layout
+    pub tools: Vec<String>,
+    /// Resolve dependency versions without contacting the registry
+    pub offline: bool,
+    /// Where dependency versions are looked up
+    pub registry: Registry,
+    /// Versions chosen by the user instead of the latest, by crate name
+    pub version_pins: BTreeMap<String, VersionPin>,
+    /// Template variables passed with `--var KEY=VALUE`
+    pub variables: HashMap<String, String>,
+    /// What the `.gdextension` file lists
+    pub extension: ExtensionEntries,
+}
+
+#[cfg(test)]
+impl GeneratorInput {
+    /// Input for a Godot 4.3 mobile project with nothing else passed on the
+    /// command line and no registry lookups
+    pub fn for_tests(project_name: &str, layout: crate::utils::layout::ProjectLayout) -> Self {
+        Self {
+            project_name: project_name.to_string(),
+            engine_version: "4.3".parse().unwrap(),
+            rendering_method: RenderingMethod::Mobile,
+            paths: crate::utils::project::Project::default()
+                .paths(project_name, layout)
+                .unwrap(),
+            tools: Vec::new(),
+            offline: true,
+            registry: Registry::default(),
+            version_pins: BTreeMap::new(),
+            variables: HashMap::new(),
+            extension: ExtensionEntries::default(),
+        }
+    }
+}
+
+/// Work to run once all template files have been written
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PostGenerationStep {
+    /// Runs `cargo build` in a directory relative to the project root
+    CargoBuild(PathBuf),
+}
+
+/// A project template
+pub trait Generator: Send + Sync {
+    /// Template name, matching its directory name
+    fn name(&self) -> &str;
+
+    /// One line description shown by `godust list templates`
+    fn description(&self) -> &str;
+
+    /// Manifest the generator was built from, if any
+    fn manifest(&self) -> Option<&TemplateManifest> {
+        None
+    }
+
+    /// Variables the template declares
+    fn variables(&self) -> &[VariableSpec] {
+        self.manifest().map_or(&[], |m| m.variables.as_slice())
+    }
+
+    /// Context variables the template files and paths rely on
+    fn required_variables(&self) -> Vec<String>;
+
+    /// Builds the Tera Context with all variables required by the template
+    fn build_context<'a>(&'a self, input: &'a GeneratorInput) -> ContextFuture<'a>;
+
+    /// Steps to run after the files are generated
+    ///
+    /// By default the Rust core crate (or the whole workspace) is built so
+    /// the Godot project can be opened right away.
+    fn post_generation_steps(&self, input: &GeneratorInput) -> Vec<PostGenerationStep> {
+        vec![PostGenerationStep::CargoBuild(input.paths.build_dir())]
+    }
+}
+
+/// Returns a generator for every template embedded in the binary
+pub fn registry() -> &'static [Arc<dyn Generator>] {
+    static GENERATORS: OnceLock<Vec<Arc<dyn Generator>>> = OnceLock::new();
+
+    GENERATORS.get_or_init(|| {
+        crate::TEMPLATES
+            .dirs()
+            .map(|dir| {
+                let manifest = TemplateManifest::load(&TemplateSource::Embedded(dir))
+                    .ok()
+                    .flatten()
+                    .unwrap_or_else(|| {
+                        panic!(
+                            "embedded template {} has an invalid manifest",
+                            dir.path().display()
+                        )
+                    });
+                ManifestGenerator::shared(manifest)
+            })
+            .collect()
+    })
+}
+
+/// Finds an embedded template's generator by name
+pub fn find(name: &str) -> Option<Arc<dyn Generator>> {
+    registry().iter().find(|g| g.name() == name).cloned()
+}
+
+/// A template ready to be rendered: where its files live and how its context is built
+pub struct Template {
+    pub name: String,
+    pub source: TemplateSource,
+    pub generator: Arc<dyn Generator>,
+}
+
+/// Resolves the template to render
+///
+/// `template_path` takes precedence over everything else. Otherwise a
+/// template with this name in the user templates directory is preferred
+/// over the embedded one, so studios can override built-in templates.
+/// Local templates are described by their own manifest; without one they
+/// reuse the embedded template's generator with the same name, or a default.
+pub fn resolve(name: &str, template_path: Option<&Path>) -> Result<Option<Template>, GodustError> {
+    let local_dir = match template_path {
+        Some(path) => Some(path.to_path_buf()),
+        None => paths::user_templates_dir()
+            .map(|dir| dir.join(name))
+            .filter(|dir| dir.is_dir()),
+    };
+
+    if let Some(dir) = local_dir {
+        let source = TemplateSource::Local(dir.clone());
+        let dir_name = dir
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| name.to_string());
+
+        let generator = match TemplateManifest::load(&source)? {
+            Some(manifest) => ManifestGenerator::shared(manifest),
+            None => find(name).unwrap_or_else(|| {
+                ManifestGenerator::shared(TemplateManifest::local_default(&dir_name))
+            }),
+        };
+
+        return Ok(Some(Template {
+            name: dir_name,
+            source,
+            generator,
+        }));
+    }
+
+    let Some(generator) = find(name) else {
+        return Ok(None);
+    };
+
+    let embedded = crate::get_embedded_template(name)?;
+    Ok(Some(Template {
+        name: name.to_string(),
+        source: TemplateSource::Embedded(embedded),
+        generator,
+    }))
+}
+
+/// Returns the templates found in the user templates directory, by
+/// directory name, along with the ones that failed to load
+pub fn user_templates() -> Vec<(String, Result<Template, GodustError>)> {
+    let Some(entries) = paths::user_templates_dir().and_then(|dir| fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+
+    let mut names: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let template = resolve(&name, None).transpose()?;
+            Some((name, template))
+        })
+        .collect()
+}
+
+/// Returns the names of every available template, built-in and user-provided
+pub fn template_names() -> Vec<String> {
+    let mut names: Vec<String> = registry().iter().map(|g| g.name().to_string()).collect();
+    for (_, template) in user_templates() {
+        if let Ok(template) = template
+            && !names.contains(&template.name)
+        {
+            names.push(template.name);
+        }
+    }
+    names
+}
+
+/// Builds the context variables shared by all templates
+///
+/// Inserts the project name variations, engine version, rendering method and
+/// layout.
+pub fn base_context(input: &GeneratorInput) -> Context {
+    let mut context = Context::new();
+
+    // Derive name variations
+    let detected_casing = naming::detect_casing(&input.project_name);
+    let kebab_name = naming::split_into_kebab_case(&input.project_name, detected_casing);
+    let pascal_name = naming::split_into_pascal_case(&input.project_name, detected_casing);
+
+    context.insert("project_name", &input.project_name);
+    context.insert("project_kebab_name", &kebab_name);
+    context.insert("struct_name", &pascal_name);
+    context.insert("engine_version", &input.engine_version.feature_tag());
+    context.insert("rendering_method", input.rendering_method.identifier());
+    context.insert(
+        "rendering_method_formatted",
+        input.rendering_method.display_name(),
+    );
+    let paths = &input.paths;
+    context.insert("layout", paths.layout.identifier());
+    context.insert("godot_dir", &paths.godot_dir);
+    context.insert("core_dir", &paths.core_dir);
+    context.insert("cargo_target_dir", &paths.target_dir());
+    context.insert("library_dir", &paths.library_dir());
+    context.insert("crates_dir", &paths.crates_dir());
+    context.insert("workspace_members", &paths.workspace_members(&input.tools));
+    context.insert("tools", &input.tools);
+    context.insert(
+        "gdextension_libraries",
+        &input
+            .extension
+            .libraries(&paths.library_dir(), &input.project_name),
+    );
+    context.insert("gdextension_icons", &input.extension.icons());
+    context.insert(
+        "gdextension_dependencies",
+        &input.extension.dependencies(&paths.library_dir()),
+    );
+    // Refined by generators that know the godot crate version
+    context.insert("compatibility_minimum", &input.engine_version.feature_tag());
+
+    context
+}
+
+/// Builds the context for `generator` and checks every required variable is set
+pub async fn build_checked_context(
+    generator: &dyn Generator,
+    input: &GeneratorInput,
+) -> Result<Context, GodustError> {
+    let context = generator.build_context(input).await?;
+
+    let missing: Vec<String> = generator
+        .required_variables()
+        .into_iter()
+        .filter(|var| !context.contains_key(var))
+        .collect();
+
+    if !missing.is_empty() {
+        return Err(GodustError::InvalidTemplate(format!(
+            "Template '{}' is missing context variables: {}",
+            generator.name(),
+            missing.join(", ")
+        )));
+    }
+
+    Ok(context)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::layout::ProjectLayout;
+    use crate::utils::template_manifest::BUILTIN_VARIABLES;
+
+    #[test]
+    fn registry_contains_every_embedded_template() {
+        for dir in crate::TEMPLATES.dirs() {
+            let name = dir.path().to_str().unwrap();
+            assert!(
+                find(name).is_some(),
+                "src/templates/{} is not registered",
+                name
+            );
+        }
+        assert!(find("unknown").is_none());
+    }
+
+    #[test]
+    fn base_context_derives_name_variations() {
+        let input = GeneratorInput::for_tests("my_game", ProjectLayout::Workspace);
+        let context = base_context(&input);
+
+        assert_eq!(context.get("project_kebab_name").unwrap(), "my-game");
+        assert_eq!(context.get("struct_name").unwrap(), "MyGame");
+        assert_eq!(context.get("engine_version").unwrap(), "4.3");
+        assert_eq!(context.get("rendering_method_formatted").unwrap(), "Mobile");
+        assert_eq!(context.get("cargo_target_dir").unwrap(), "target");
+        assert_eq!(context.get("library_dir").unwrap(), "res://../target");
+    }
+
+    #[test]
+    fn base_context_provides_exactly_the_builtin_variables() {
+        let input = GeneratorInput::for_tests("my_game", ProjectLayout::Workspace);
+        let context = base_context(&input).into_json();
+        let mut keys: Vec<&str> = context
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(String::as_str)
+            .collect();
+        let mut builtin = BUILTIN_VARIABLES.to_vec();
+        keys.sort_unstable();
+        builtin.sort_unstable();
+
+        assert_eq!(keys, builtin);
+    }
+}
@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use super::{ContextFuture, Generator, GeneratorInput};
+use crate::error::GodustError;
+use crate::utils::compatibility;
+use crate::utils::resolver::{DependencyRequest, VersionResolver};
+use crate::utils::template_manifest::{GODOT_API_FEATURE, TemplateManifest, VariableSpec};
+use crate::utils::version_cache::VersionCache;
+
+/// Generator driven entirely by a template's `template.toml`
+///
+/// Builds the shared context, resolves every declared crate dependency
+/// concurrently from the registry and fills in the declared variables from
+/// `--var` options or their defaults. Unless pinned, the `godot` crate comes
+/// from the newest godot-rust line able to target the requested engine.
+#[derive(Debug, Clone)]
+pub struct ManifestGenerator {
+    manifest: TemplateManifest,
+}
+
+impl ManifestGenerator {
+    pub fn shared(manifest: TemplateManifest) -> Arc<dyn Generator> {
+        Arc::new(Self { manifest })
+    }
+}
+
+impl Generator for ManifestGenerator {
+    fn name(&self) -> &str {
+        &self.manifest.name
+    }
+
+    fn description(&self) -> &str {
+        &self.manifest.description
+    }
+
+    fn manifest(&self) -> Option<&TemplateManifest> {
+        Some(&self.manifest)
+    }
+
+    fn variables(&self) -> &[VariableSpec] {
+        &self.manifest.variables
+    }
+
+    fn required_variables(&self) -> Vec<String> {
+        let mut required = self.manifest.builtin_variables();
+        required.extend(
+            self.manifest
+                .variables
+                .iter()
+                .filter(|v| v.required)
+                .map(|v| v.name.clone()),
+        );
+        required
+    }
+
+    fn build_context<'a>(&'a self, input: &'a GeneratorInput) -> ContextFuture<'a> {
+        Box::pin(async move {
+            self.manifest.check_engine_version(&input.engine_version)?;
+
+            let mut context = super::base_context(input);
+
+            // Pinned or latest crate versions from the registry, the cache or a fallback
+            let requests = self
+                .manifest
+                .dependencies
+                .iter()
+                .map(|(crate_name, fallback)| {
+                    let pin = match input.version_pins.get(crate_name) {
+                        None if crate_name == "godot" => {
+                            Some(compatibility::godot_crate_pin(&input.engine_version)?)
+                        }
+                        pin => pin.cloned(),
+                    };
+                    Ok(DependencyRequest {
+                        crate_name: crate_name.clone(),
+                        template_fallback: fallback.clone(),
+                        pin,
+                    })
+                })
+                .collect::<Result<Vec<_>, String>>()
+                .map_err(GodustError::Usage)?;
+
+            let resolver =
+                VersionResolver::new(input.registry.clone(), input.offline, VersionCache::load())?;
+            let report = resolver.resolve_all(requests).await?;
+
+            println!("📦 Resolved {}", report.summary());
+            for (crate_name, resolved) in &report.dependencies {
+                println!(
+                    "   {} {} ({})",
+                    crate_name,
+                    resolved.version,
+                    resolved.source.describe()
+                );
+                context.insert(
+                    TemplateManifest::dependency_variable(crate_name),
+                    &resolved.version,
+                );
+            }
+
+            if let Some(godot) = report.get("godot") {
+                let target = compatibility::engine_target(&godot.version, &input.engine_version)
+                    .map_err(GodustError::Usage)?;
+                context.insert(GODOT_API_FEATURE, &target.api_feature);
+                context.insert("compatibility_minimum", &target.compatibility_minimum);
+            }
+
+            self.manifest
+                .resolve_variables(&mut context, &input.variables)?;
+
+            Ok(context)
+        })
+    }
+}
@@ -1,5 +1,22 @@
+pub mod cargo;
+pub mod compatibility;
+pub mod config;
 pub mod crates_version;
+pub mod engine;
+pub mod file_plan;
+pub mod gdextension;
+pub mod godot_config;
+pub mod godot_project;
+pub mod layout;
+pub mod name_validation;
 pub mod naming;
+pub mod paths;
 pub mod project;
+pub mod prompt;
+pub mod registry;
+pub mod resolver;
+pub mod staging;
+pub mod template_manifest;
 pub mod template_parser;
 pub mod uid_generator;
+pub mod version_cache;
@@ -0,0 +1,227 @@
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+
+use crate::error::GodustError;
+
+/// How the generated crate is built once the project is generated
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BuildOptions {
+    /// Build with the release profile instead of the dev one
+    pub release: bool,
+    /// Target triple to build for, the host when `None`
+    pub target: Option<String>,
+    /// Cargo features to enable
+    pub features: Vec<String>,
+}
+
+impl BuildOptions {
+    /// Arguments passed to cargo, starting with `build`
+    pub fn cargo_args(&self) -> Vec<String> {
+        let mut args = vec!["build".to_string()];
+        if self.release {
+            args.push("--release".to_string());
+        }
+        if let Some(target) = &self.target {
+            args.extend(["--target".to_string(), target.clone()]);
+        }
+        if !self.features.is_empty() {
+            args.extend(["--features".to_string(), self.features.join(",")]);
+        }
+        args
+    }
+
+    /// Godot feature tags the built library is loaded for, e.g.
+    /// `["linux", "debug", "x86_64"]`
+    ///
+    /// `None` when godust doesn't know how Godot calls the platform.
+    pub fn godot_features(&self) -> Option<[&'static str; 3]> {
+        let (platform, arch) = match &self.target {
+            Some(triple) => {
+                let (arch, rest) = triple.split_once('-')?;
+                let os = ["android", "ios", "darwin", "windows", "linux", "emscripten"]
+                    .into_iter()
+                    .find(|os| rest.split('-').any(|part| part.starts_with(os)))?;
+                (godot_platform(os)?, godot_arch(arch)?)
+            }
+            None => (
+                godot_platform(std::env::consts::OS)?,
+                godot_arch(std::env::consts::ARCH)?,
+            ),
+        };
+        let build = if self.release { "release" } else { "debug" };
+
+        Some([platform, build, arch])
+    }
+}
+
+/// Godot's name for an operating system from a target triple or
+/// `std::env::consts::OS`
+fn godot_platform(os: &str) -> Option<&'static str> {
+    match os {
+        "linux" => Some("linux"),
+        "windows" => Some("windows"),
+        "macos" | "darwin" => Some("macos"),
+        "android" => Some("android"),
+        "ios" => Some("ios"),
+        "emscripten" => Some("web"),
+        _ => None,
+    }
+}
+
+/// Godot's name for a CPU architecture from a target triple or
+/// `std::env::consts::ARCH`
+fn godot_arch(arch: &str) -> Option<&'static str> {
+    match arch {
+        "x86_64" => Some("x86_64"),
+        "aarch64" | "arm64" => Some("arm64"),
+        "x86" | "i586" | "i686" => Some("x86_32"),
+        "wasm32" => Some("wasm32"),
+        "riscv64" | "riscv64gc" => Some("rv64"),
+        arch if arch.starts_with("arm") || arch.starts_with("thumbv7") => Some("arm32"),
+        _ => None,
+    }
+}
+
+/// Runs `cargo build` in `crate_dir`
+///
+/// Cargo's progress and diagnostics go straight to the terminal while it
+/// runs. Returns the dynamic libraries that were built.
+pub async fn build(crate_dir: &Path, options: &BuildOptions) -> Result<Vec<PathBuf>, GodustError> {
+    let could_not_run = |e: std::io::Error| {
+        GodustError::Build(format!(
+            "Could not run cargo build in {}: {}",
+            crate_dir.display(),
+            e
+        ))
+    };
+
+    let mut child = Command::new("cargo")
+        .args(options.cargo_args())
+        .arg("--message-format=json-render-diagnostics")
+        .current_dir(crate_dir)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .map_err(could_not_run)?;
+
+    // Diagnostics are rendered on stderr; stdout only carries JSON messages
+    let mut libraries = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        let mut lines = BufReader::new(stdout).lines();
+        while let Some(line) = lines.next_line().await.map_err(could_not_run)? {
+            if let Ok(message) = serde_json::from_str::<Value>(&line) {
+                libraries.extend(built_libraries(&message));
+            }
+        }
+    }
+
+    let status = child.wait().await.map_err(could_not_run)?;
+    if !status.success() {
+        return Err(GodustError::Build(format!(
+            "cargo build failed in {}; fix the errors above and run it again",
+            crate_dir.display()
+        )));
+    }
+
+    Ok(libraries)
+}
+
+/// The `cdylib` files of a `compiler-artifact` message
+fn built_libraries(message: &Value) -> Vec<PathBuf> {
+    let is_cdylib = message["target"]["kind"]
+        .as_array()
+        .is_some_and(|kinds| kinds.iter().any(|kind| kind == "cdylib"));
+    if message["reason"] != "compiler-artifact" || !is_cdylib {
+        return Vec::new();
+    }
+
+    message["filenames"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .map(PathBuf::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn passes_build_options_to_cargo() {
+        assert_eq!(BuildOptions::default().cargo_args(), ["build"]);
+
+        let options = BuildOptions {
+            release: true,
+            target: Some("aarch64-linux-android".to_string()),
+            features: vec!["serde".to_string(), "tracing".to_string()],
+        };
+        assert_eq!(
+            options.cargo_args(),
+            [
+                "build",
+                "--release",
+                "--target",
+                "aarch64-linux-android",
+                "--features",
+                "serde,tracing"
+            ]
+        );
+    }
+
+    #[test]
+    fn maps_target_triples_to_godot_features() {
+        let features = |target: &str, release: bool| {
+            BuildOptions {
+                release,
+                target: Some(target.to_string()),
+                features: Vec::new(),
+            }
+            .godot_features()
+        };
+
+        assert_eq!(
+            features("x86_64-unknown-linux-gnu", false),
+            Some(["linux", "debug", "x86_64"])
+        );
+        assert_eq!(
+            features("aarch64-apple-darwin", true),
+            Some(["macos", "release", "arm64"])
+        );
+        assert_eq!(
+            features("armv7-linux-androideabi", false),
+            Some(["android", "debug", "arm32"])
+        );
+        assert_eq!(
+            features("i686-pc-windows-msvc", false),
+            Some(["windows", "debug", "x86_32"])
+        );
+        assert_eq!(
+            features("wasm32-unknown-emscripten", false),
+            Some(["web", "debug", "wasm32"])
+        );
+        assert_eq!(features("x86_64-unknown-freebsd", false), None);
+    }
+
+    #[test]
+    fn collects_only_cdylib_artifacts() {
+        let artifact = |kind: &str, file: &str| {
+            serde_json::json!({
+                "reason": "compiler-artifact",
+                "target": { "kind": [kind] },
+                "filenames": [file],
+            })
+        };
+
+        assert_eq!(
+            built_libraries(&artifact("cdylib", "/p/target/debug/libdemo.so")),
+            [PathBuf::from("/p/target/debug/libdemo.so")]
+        );
+        assert!(built_libraries(&artifact("lib", "/p/target/debug/libgame_logic.rlib")).is_empty());
+        assert!(built_libraries(&serde_json::json!({ "reason": "build-finished" })).is_empty());
+    }
+}
@@ -0,0 +1,160 @@
+use semver::{Version, VersionReq};
+
+use crate::utils::crates_version::VersionPin;
+use crate::utils::engine::EngineVersion;
+
+/// A godot-rust release line and the Godot versions it can target
+struct GodotRustLine {
+    /// Requirement selecting the release line
+    crate_line: &'static str,
+    /// Oldest Godot 4 minor version it has an `api-4-x` feature for
+    oldest_api: u32,
+    /// Godot 4 minor version it targets without an `api-*` feature
+    default_api: u32,
+}
+
+/// godot-rust release lines, newest first
+///
+/// An extension built against the API of Godot 4.x loads in 4.x and every
+/// later 4.y, so a line also supports engines newer than its default API.
+const COMPATIBILITY_MATRIX: &[GodotRustLine] = &[
+    GodotRustLine {
+        crate_line: "0.4",
+        oldest_api: 2,
+        default_api: 5,
+    },
+    GodotRustLine {
+        crate_line: "0.3",
+        oldest_api: 1,
+        default_api: 4,
+    },
+    GodotRustLine {
+        crate_line: "0.2",
+        oldest_api: 1,
+        default_api: 3,
+    },
+    GodotRustLine {
+        crate_line: "0.1",
+        oldest_api: 0,
+        default_api: 2,
+    },
+];
+
+/// How the generated crate targets the requested engine
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EngineTarget {
+    /// `api-4-x` feature to enable on the godot crate, when its default API is newer
+    pub api_feature: Option<String>,
+    /// Oldest Godot version able to load the extension (`compatibility_minimum`)
+    pub compatibility_minimum: String,
+}
+
+/// Pin selecting the newest godot-rust line that can target `engine`
+pub fn godot_crate_pin(engine: &EngineVersion) -> Result<VersionPin, String> {
+    let minor = engine.minor;
+
+    let line = COMPATIBILITY_MATRIX
+        .iter()
+        .find(|line| line.oldest_api <= minor)
+        .ok_or_else(|| format!("No godot-rust release supports Godot {}", engine))?;
+
+    let req = VersionReq::parse(line.crate_line).map_err(|e| e.to_string())?;
+    Ok(VersionPin::Requirement(req))
+}
+
+/// Picks the `api-4-x` feature and `compatibility_minimum` for `godot_version` on `engine`
+///
+/// Fails when that godot-rust version can't target an engine this old.
+/// Versions newer than the matrix are assumed to target `engine` by default.
+pub fn engine_target(godot_version: &str, engine: &EngineVersion) -> Result<EngineTarget, String> {
+    let minor = engine.minor;
+    let version = Version::parse(godot_version)
+        .map_err(|e| format!("Invalid godot crate version '{}': {}", godot_version, e))?;
+
+    let line = COMPATIBILITY_MATRIX
+        .iter()
+        .find(|line| VersionReq::parse(line.crate_line).is_ok_and(|req| req.matches(&version)));
+
+    let Some(line) = line else {
+        return Ok(EngineTarget {
+            api_feature: None,
+            compatibility_minimum: format!("4.{}", minor),
+        });
+    };
+
+    if minor < line.oldest_api {
+        return Err(format!(
+            "godot {} can't target Godot {}, it supports Godot 4.{} and newer",
+            godot_version, engine, line.oldest_api
+        ));
+    }
+
+    if minor < line.default_api {
+        Ok(EngineTarget {
+            api_feature: Some(format!("api-4-{}", minor)),
+            compatibility_minimum: format!("4.{}", minor),
+        })
+    } else {
+        Ok(EngineTarget {
+            api_feature: None,
+            compatibility_minimum: format!("4.{}", line.default_api),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn engine(raw: &str) -> EngineVersion {
+        raw.parse().unwrap()
+    }
+
+    #[test]
+    fn selects_newest_line_supporting_the_engine() {
+        let pin = |raw: &str| godot_crate_pin(&engine(raw)).unwrap().to_string();
+
+        assert_eq!(pin("4.5"), "^0.4");
+        assert_eq!(pin("4.6"), "^0.4");
+        assert_eq!(pin("4.2.1"), "^0.4");
+        assert_eq!(pin("4.1"), "^0.3");
+        assert_eq!(pin("4.0"), "^0.1");
+    }
+
+    #[test]
+    fn older_engines_get_an_api_feature() {
+        assert_eq!(
+            engine_target("0.4.0", &engine("4.3")).unwrap(),
+            EngineTarget {
+                api_feature: Some("api-4-3".to_string()),
+                compatibility_minimum: "4.3".to_string(),
+            }
+        );
+        assert_eq!(
+            engine_target("0.1.3", &engine("4.0"))
+                .unwrap()
+                .api_feature
+                .as_deref(),
+            Some("api-4-0")
+        );
+    }
+
+    #[test]
+    fn newer_engines_use_the_default_api() {
+        for raw in ["4.5", "4.6"] {
+            assert_eq!(
+                engine_target("0.4.2", &engine(raw)).unwrap(),
+                EngineTarget {
+                    api_feature: None,
+                    compatibility_minimum: "4.5".to_string(),
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_engines_older_than_the_crate_supports() {
+        assert!(engine_target("0.4.0", &engine("4.1")).is_err());
+        assert!(engine_target("0.3.0", &engine("4.0")).is_err());
+    }
+}
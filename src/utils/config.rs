@@ -0,0 +1,112 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::error::GodustError;
+use crate::utils::crates_version::VersionPin;
+use crate::utils::paths;
+
+const CONFIG_FILE: &str = "config.toml";
+
+/// User settings read from `<config dir>/config.toml`
+///
+/// ```toml
+/// registry = "sparse+https://index.crates.io/"
+///
+/// [layout]
+/// godot_dir = "godot"
+/// core_dir = "rust"
+///
+/// [versions]
+/// godot = "0.4"
+/// bevy_ecs = "0.17.2"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Registry URL, see [`Registry::parse`](crate::utils::registry::Registry::parse)
+    pub registry: Option<String>,
+    /// Default project directories
+    #[serde(default)]
+    pub layout: LayoutConfig,
+    /// Version or requirement pinned per crate
+    #[serde(default)]
+    pub versions: BTreeMap<String, String>,
+}
+
+/// The `[layout]` table, see [`Project`](crate::utils::project::Project)
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LayoutConfig {
+    pub godot_dir: Option<String>,
+    pub core_dir: Option<String>,
+}
+
+impl Config {
+    /// Loads the user's config file; the defaults when there is none
+    pub fn load() -> Result<Self, GodustError> {
+        match config_file() {
+            Some(path) if path.is_file() => {
+                let invalid =
+                    |e: &dyn Error| GodustError::Config(format!("{}: {}", path.display(), e));
+                let contents = fs::read_to_string(&path).map_err(|e| invalid(&e))?;
+                Self::parse(&contents).map_err(|e| invalid(&e))
+            }
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn parse(contents: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(contents)
+    }
+
+    /// Parses the `[versions]` table
+    pub fn version_pins(&self) -> Result<BTreeMap<String, VersionPin>, GodustError> {
+        self.versions
+            .iter()
+            .map(|(crate_name, raw)| {
+                let pin = raw.parse().map_err(|e| {
+                    GodustError::Config(format!("[versions] {}: {}", crate_name, e))
+                })?;
+                Ok((crate_name.clone(), pin))
+            })
+            .collect()
+    }
+}
+
+/// Returns the path of the config file (`<config dir>/config.toml`)
+pub fn config_file() -> Option<PathBuf> {
+    paths::config_dir().map(|dir| dir.join(CONFIG_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_version_pins() {
+        let config = Config::parse("[versions]\ngodot = \"0.4\"\nbevy_ecs = \"0.17.2\"\n").unwrap();
+        let pins = config.version_pins().unwrap();
+
+        assert_eq!(pins["godot"], "^0.4".parse().unwrap());
+        assert_eq!(pins["bevy_ecs"], "0.17.2".parse().unwrap());
+    }
+
+    #[test]
+    fn parses_layout_paths() {
+        let config = Config::parse("[layout]\ngodot_dir = \"godot\"\n").unwrap();
+
+        assert_eq!(config.layout.godot_dir.as_deref(), Some("godot"));
+        assert_eq!(config.layout.core_dir, None);
+    }
+
+    #[test]
+    fn rejects_invalid_config() {
+        assert!(Config::parse("[version]\ngodot = \"0.4\"\n").is_err());
+
+        let config = Config::parse("[versions]\ngodot = \"newest\"\n").unwrap();
+        assert!(config.version_pins().is_err());
+    }
+}
@@ -1,33 +1,126 @@
-use serde::Deserialize;
-use std::error::Error;
+use semver::{Version, VersionReq};
+use std::fmt;
+use std::str::FromStr;
 
-#[derive(Deserialize)]
-struct CrateApiResponse {
-    #[serde(rename = "crate")]
-    crate_data: CrateInfo,
+/// Versions known to work with the bundled templates, newest first
+///
+/// Used when the registry can't be reached and nothing is cached. Crates not
+/// listed here fall back to the version declared in the template manifest.
+/// Older godot lines are listed for engines the newest one can't target.
+pub const KNOWN_GOOD_VERSIONS: &[(&str, &str)] = &[
+    ("godot", "0.4.0"),
+    ("godot", "0.3.0"),
+    ("godot", "0.2.0"),
+    ("godot", "0.1.3"),
+    ("bevy_ecs", "0.17.2"),
+];
+
+/// A dependency version chosen by the user instead of the latest one
+#[derive(Debug, Clone, PartialEq)]
+pub enum VersionPin {
+    /// `0.4.1`: written to `Cargo.toml` as is
+    Exact(Version),
+    /// `0.4`, `~0.4.1`, `>=0.3, <0.5`: the newest published version matching it
+    Requirement(VersionReq),
+}
+
+impl FromStr for VersionPin {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let raw = raw.trim();
+        if let Ok(version) = Version::parse(raw) {
+            return Ok(VersionPin::Exact(version));
+        }
+
+        VersionReq::parse(raw)
+            .map(VersionPin::Requirement)
+            .map_err(|e| format!("'{}' is not a version or requirement: {}", raw, e))
+    }
+}
+
+impl fmt::Display for VersionPin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VersionPin::Exact(version) => write!(f, "{}", version),
+            VersionPin::Requirement(req) => write!(f, "{}", req),
+        }
+    }
+}
+
+/// Where a resolved version came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionSource {
+    /// Fetched from the registry during this run
+    Network,
+    /// Taken from the on-disk cache
+    Cache,
+    /// An exact version passed by the user
+    Pinned,
+    /// Taken from [`KNOWN_GOOD_VERSIONS`]
+    KnownGood,
+    /// The version declared in the template manifest
+    TemplateFallback,
 }
 
-#[derive(Deserialize)]
-struct CrateInfo {
-    max_stable_version: String,
+impl VersionSource {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            VersionSource::Network => "registry",
+            VersionSource::Cache => "cache",
+            VersionSource::Pinned => "pinned",
+            VersionSource::KnownGood => "known-good fallback",
+            VersionSource::TemplateFallback => "template fallback",
+        }
+    }
 }
 
-pub async fn get_crate_version(crate_name: &str) -> Result<String, Box<dyn Error>> {
-    let url = format!("https://crates.io/api/v1/crates/{}", crate_name);
+/// A dependency version and where it came from
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedVersion {
+    pub version: String,
+    pub source: VersionSource,
+}
+
+/// Picks the highest version satisfying `req`
+///
+/// Pre-releases only match requirements that name a pre-release, as in Cargo.
+pub fn newest_matching<'a>(versions: &'a [Version], req: &VersionReq) -> Option<&'a Version> {
+    versions.iter().filter(|v| req.matches(v)).max()
+}
 
-    let client = reqwest::Client::new();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let response = client
-        .get(&url)
-        .header(
-            reqwest::header::USER_AGENT,
-            "GodustCLI/0.1 (https://github.com/viniciusmorgado/godust)",
-        )
-        .send()
-        .await?
-        .error_for_status()?;
+    #[test]
+    fn parses_exact_versions_and_requirements() {
+        assert_eq!(
+            "0.4.1".parse::<VersionPin>(),
+            Ok(VersionPin::Exact(Version::new(0, 4, 1)))
+        );
+        assert_eq!(
+            "0.4".parse::<VersionPin>(),
+            Ok(VersionPin::Requirement(VersionReq::parse("^0.4").unwrap()))
+        );
+        assert!(">=0.3, <0.5".parse::<VersionPin>().is_ok());
+        assert!("latest".parse::<VersionPin>().is_err());
+    }
 
-    let crate_api_response: CrateApiResponse = response.json().await?;
+    #[test]
+    fn picks_newest_matching_stable_version() {
+        let versions: Vec<Version> = ["0.3.5", "0.4.0", "0.4.2", "0.5.0-rc.1", "0.5.0"]
+            .iter()
+            .map(|v| Version::parse(v).unwrap())
+            .collect();
+        let newest = |req: &str| {
+            newest_matching(&versions, &VersionReq::parse(req).unwrap()).map(|v| v.to_string())
+        };
 
-    Ok(crate_api_response.crate_data.max_stable_version)
+        assert_eq!(newest("0.4").as_deref(), Some("0.4.2"));
+        assert_eq!(newest(">=0.3, <0.5").as_deref(), Some("0.4.2"));
+        assert_eq!(newest("*").as_deref(), Some("0.5.0"));
+        assert_eq!(newest("=0.5.0-rc.1").as_deref(), Some("0.5.0-rc.1"));
+        assert_eq!(newest("0.6"), None);
+    }
 }
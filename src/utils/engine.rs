@@ -0,0 +1,205 @@
+use clap::ValueEnum;
+use std::fmt;
+use std::str::FromStr;
+
+/// Godot engine versions offered by the project wizard, newest first
+pub const KNOWN_ENGINE_VERSIONS: &[&str] = &["4.5", "4.4", "4.3", "4.2", "4.1"];
+
+/// Release statuses Godot uses in version names (`4.4-beta2`, `4.3-stable`)
+const STATUSES: &[&str] = &["stable", "rc", "beta", "alpha", "dev"];
+
+/// A Godot 4 engine version: `major.minor[.patch][-status]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EngineVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: Option<u32>,
+    /// Release status such as `stable`, `rc1` or `beta2`
+    pub status: Option<String>,
+}
+
+impl EngineVersion {
+    /// The `major.minor` form Godot writes to `config/features` in `project.godot`
+    pub fn feature_tag(&self) -> String {
+        format!("{}.{}", self.major, self.minor)
+    }
+
+    /// Whether this version is `minimum` or newer, ignoring the release status
+    pub fn is_at_least(&self, minimum: &EngineVersion) -> bool {
+        let numbers = |v: &EngineVersion| (v.major, v.minor, v.patch.unwrap_or(0));
+        numbers(self) >= numbers(minimum)
+    }
+}
+
+impl FromStr for EngineVersion {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            format!(
+                "'{}' is not a Godot version like 4.3, 4.3.1 or 4.4-beta2",
+                raw
+            )
+        };
+
+        let (numbers, status) = match raw.split_once('-') {
+            Some((numbers, status)) => (numbers, Some(status)),
+            None => (raw, None),
+        };
+
+        let parts: Vec<u32> = numbers
+            .split('.')
+            .map(|part| {
+                if part.is_empty() || !part.chars().all(|c| c.is_ascii_digit()) {
+                    return Err(invalid());
+                }
+                part.parse().map_err(|_| invalid())
+            })
+            .collect::<Result<_, _>>()?;
+
+        let (major, minor, patch) = match parts[..] {
+            [major, minor] => (major, minor, None),
+            [major, minor, patch] => (major, minor, Some(patch)),
+            _ => return Err(invalid()),
+        };
+
+        if let Some(status) = status {
+            let name = status.trim_end_matches(|c: char| c.is_ascii_digit());
+            if !STATUSES.contains(&name) {
+                return Err(format!(
+                    "'{}' has an unknown release status '{}' (expected one of: {})",
+                    raw,
+                    status,
+                    STATUSES.join(", ")
+                ));
+            }
+        }
+
+        if major != 4 {
+            return Err(format!(
+                "godust generates Godot 4 projects, but version {} was requested",
+                raw
+            ));
+        }
+
+        Ok(EngineVersion {
+            major,
+            minor,
+            patch,
+            status: status.map(str::to_string),
+        })
+    }
+}
+
+impl fmt::Display for EngineVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)?;
+        if let Some(patch) = self.patch {
+            write!(f, ".{}", patch)?;
+        }
+        if let Some(status) = &self.status {
+            write!(f, "-{}", status)?;
+        }
+        Ok(())
+    }
+}
+
+/// Renderers accepted by Godot's `renderer/rendering_method`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum RenderingMethod {
+    #[default]
+    #[value(name = "forward_plus", alias = "forward-plus")]
+    ForwardPlus,
+    #[value(name = "gl_compatibility", alias = "gl-compatibility")]
+    GlCompatibility,
+    #[value(name = "mobile")]
+    Mobile,
+}
+
+impl RenderingMethod {
+    /// Identifier written to `renderer/rendering_method`
+    pub fn identifier(&self) -> &'static str {
+        match self {
+            RenderingMethod::ForwardPlus => "forward_plus",
+            RenderingMethod::GlCompatibility => "gl_compatibility",
+            RenderingMethod::Mobile => "mobile",
+        }
+    }
+
+    /// Name Godot writes to `config/features`
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            RenderingMethod::ForwardPlus => "Forward Plus",
+            RenderingMethod::GlCompatibility => "GL Compatibility",
+            RenderingMethod::Mobile => "Mobile",
+        }
+    }
+}
+
+impl fmt::Display for RenderingMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.identifier())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_engine_versions() {
+        let version: EngineVersion = "4.4.1-rc2".parse().unwrap();
+        assert_eq!(
+            version,
+            EngineVersion {
+                major: 4,
+                minor: 4,
+                patch: Some(1),
+                status: Some("rc2".to_string()),
+            }
+        );
+        assert_eq!(version.to_string(), "4.4.1-rc2");
+        assert_eq!(version.feature_tag(), "4.4");
+
+        assert_eq!("4.3".parse::<EngineVersion>().unwrap().to_string(), "4.3");
+        assert!("4.3-stable".parse::<EngineVersion>().is_ok());
+    }
+
+    #[test]
+    fn rejects_invalid_engine_versions() {
+        for raw in [
+            "",
+            "4",
+            "4.x",
+            "4.3.1.2",
+            "v4.3",
+            "4.3-nightly",
+            "3.6",
+            "4..3",
+        ] {
+            assert!(raw.parse::<EngineVersion>().is_err(), "{}", raw);
+        }
+    }
+
+    #[test]
+    fn compares_versions_ignoring_status() {
+        let version = |raw: &str| raw.parse::<EngineVersion>().unwrap();
+
+        assert!(version("4.10").is_at_least(&version("4.2")));
+        assert!(version("4.2-beta1").is_at_least(&version("4.2")));
+        assert!(!version("4.1.4").is_at_least(&version("4.2")));
+    }
+
+    #[test]
+    fn rendering_methods_match_godot_identifiers() {
+        for method in RenderingMethod::value_variants() {
+            let parsed = RenderingMethod::from_str(method.identifier(), false).unwrap();
+            assert_eq!(parsed, *method);
+        }
+        assert_eq!(
+            RenderingMethod::from_str("gl-compatibility", false).unwrap(),
+            RenderingMethod::GlCompatibility
+        );
+        assert!(RenderingMethod::from_str("vulkan", false).is_err());
+    }
+}
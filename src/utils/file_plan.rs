@@ -0,0 +1,182 @@
+use std::collections::BTreeMap;
+use std::path::{Component, PathBuf};
+
+/// Where the contents of a planned file come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileOrigin {
+    /// Rendered from a `.tera` file
+    Rendered,
+    /// Copied from the template as-is
+    Static,
+}
+
+impl FileOrigin {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FileOrigin::Rendered => "rendered",
+            FileOrigin::Static => "static",
+        }
+    }
+}
+
+/// A file generation is about to write, already rendered in memory
+#[derive(Debug, Clone)]
+pub struct PlannedFile {
+    /// Destination relative to the directory generated into
+    pub path: PathBuf,
+    pub contents: Vec<u8>,
+    pub origin: FileOrigin,
+}
+
+#[derive(Default)]
+struct DirNode<'a> {
+    entries: BTreeMap<String, Entry<'a>>,
+}
+
+enum Entry<'a> {
+    Dir(DirNode<'a>),
+    File(&'a PlannedFile),
+}
+
+impl<'a> DirNode<'a> {
+    fn insert(&mut self, names: &[String], file: &'a PlannedFile) {
+        match names {
+            [] => {}
+            [file_name] => {
+                self.entries.insert(file_name.clone(), Entry::File(file));
+            }
+            [dir, rest @ ..] => {
+                let entry = self
+                    .entries
+                    .entry(dir.clone())
+                    .or_insert_with(|| Entry::Dir(DirNode::default()));
+                // A file and a directory of the same name can't both be written
+                if let Entry::Dir(node) = entry {
+                    node.insert(rest, file);
+                }
+            }
+        }
+    }
+}
+
+/// Draws `files` as a tree, one line per directory or file
+///
+/// Files show their size and whether they were rendered or copied; the
+/// columns are aligned across the whole tree.
+pub fn tree_lines(files: &[PlannedFile]) -> Vec<String> {
+    let mut root = DirNode::default();
+    for file in files {
+        let names: Vec<String> = file
+            .path
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+                _ => None,
+            })
+            .collect();
+        root.insert(&names, file);
+    }
+
+    let mut rows = Vec::new();
+    collect_rows(&root, "", &mut rows);
+
+    let width = rows
+        .iter()
+        .filter(|(_, file)| file.is_some())
+        .map(|(label, _)| label.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    rows.into_iter()
+        .map(|(label, file)| match file {
+            None => label,
+            Some(file) => {
+                let padding = width - label.chars().count();
+                format!(
+                    "{}{}  {:>9}  {}",
+                    label,
+                    " ".repeat(padding),
+                    format_size(file.contents.len()),
+                    file.origin.as_str()
+                )
+            }
+        })
+        .collect()
+}
+
+fn collect_rows<'a>(
+    dir: &DirNode<'a>,
+    prefix: &str,
+    rows: &mut Vec<(String, Option<&'a PlannedFile>)>,
+) {
+    for (index, (name, entry)) in dir.entries.iter().enumerate() {
+        let last = index + 1 == dir.entries.len();
+        let branch = if last { "└── " } else { "├── " };
+        match entry {
+            Entry::Dir(node) => {
+                rows.push((format!("{}{}{}/", prefix, branch, name), None));
+                let indent = if last { "    " } else { "│   " };
+                collect_rows(node, &format!("{}{}", prefix, indent), rows);
+            }
+            Entry::File(file) => rows.push((format!("{}{}{}", prefix, branch, name), Some(file))),
+        }
+    }
+}
+
+/// `512 B`, `1.5 KiB`, `2.0 MiB`
+pub fn format_size(bytes: usize) -> String {
+    const KIB: f64 = 1024.0;
+    let size = bytes as f64;
+
+    if size < KIB {
+        format!("{} B", bytes)
+    } else if size < KIB * KIB {
+        format!("{:.1} KiB", size / KIB)
+    } else {
+        format!("{:.1} MiB", size / (KIB * KIB))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn planned(path: &str, size: usize, origin: FileOrigin) -> PlannedFile {
+        PlannedFile {
+            path: PathBuf::from(path),
+            contents: vec![b'x'; size],
+            origin,
+        }
+    }
+
+    #[test]
+    fn draws_nested_directories_with_aligned_sizes() {
+        let files = [
+            planned("demo_core/src/lib.rs", 300, FileOrigin::Rendered),
+            planned("demo/icon.svg", 2048, FileOrigin::Static),
+            planned("demo_core/Cargo.toml", 180, FileOrigin::Rendered),
+            planned(".gitignore", 12, FileOrigin::Static),
+        ];
+
+        assert_eq!(
+            tree_lines(&files),
+            [
+                "├── .gitignore           12 B  static",
+                "├── demo/",
+                "│   └── icon.svg      2.0 KiB  static",
+                "└── demo_core/",
+                "    ├── Cargo.toml      180 B  rendered",
+                "    └── src/",
+                "        └── lib.rs      300 B  rendered",
+            ]
+        );
+    }
+
+    #[test]
+    fn formats_sizes_in_binary_units() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(3 * 1024 * 1024), "3.0 MiB");
+    }
+}
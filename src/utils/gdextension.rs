@@ -0,0 +1,440 @@
+use clap::ValueEnum;
+use std::fmt;
+use std::str::FromStr;
+
+/// Platform a GDExtension library is loaded on, named after Godot's feature
+/// tag
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Platform {
+    Linux,
+    Windows,
+    Macos,
+    Android,
+    Ios,
+    Web,
+}
+
+impl Platform {
+    /// Godot feature tag, also the name passed to `--platforms`
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Platform::Linux => "linux",
+            Platform::Windows => "windows",
+            Platform::Macos => "macos",
+            Platform::Android => "android",
+            Platform::Ios => "ios",
+            Platform::Web => "web",
+        }
+    }
+
+    /// Architectures the platform can have libraries for
+    pub fn architectures(&self) -> &'static [Arch] {
+        match self {
+            Platform::Linux | Platform::Windows => &[Arch::X86_64, Arch::Arm64],
+            Platform::Macos => &[Arch::Universal, Arch::Arm64],
+            Platform::Android => &[Arch::Arm64, Arch::Arm32, Arch::X86_64, Arch::X86_32],
+            Platform::Ios => &[Arch::Arm64],
+            Platform::Web => &[Arch::Wasm32],
+        }
+    }
+
+    /// Architectures the platform gets libraries for unless one is chosen;
+    /// arm64 Linux and Windows builds need a cross target, so they are only
+    /// listed when asked for
+    pub fn default_architectures(&self) -> &'static [Arch] {
+        match self {
+            Platform::Linux | Platform::Windows => &[Arch::X86_64],
+            _ => self.architectures(),
+        }
+    }
+
+    /// File name of the dynamic library cargo builds for the crate `name`
+    pub fn library_file(&self, name: &str) -> String {
+        match self {
+            Platform::Linux | Platform::Android => format!("lib{}.so", name),
+            Platform::Windows => format!("{}.dll", name),
+            Platform::Macos | Platform::Ios => format!("lib{}.dylib", name),
+            Platform::Web => format!("{}.wasm", name),
+        }
+    }
+}
+
+/// CPU architecture of a library, named after Godot's feature tag
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Arch {
+    /// Any architecture: a macOS universal library, listed without a tag
+    Universal,
+    #[value(name = "x86_64")]
+    X86_64,
+    #[value(name = "x86_32")]
+    X86_32,
+    Arm64,
+    Arm32,
+    Wasm32,
+}
+
+impl Arch {
+    /// Godot feature tag, `None` for [`Arch::Universal`]
+    pub fn tag(&self) -> Option<&'static str> {
+        match self {
+            Arch::Universal => None,
+            Arch::X86_64 => Some("x86_64"),
+            Arch::X86_32 => Some("x86_32"),
+            Arch::Arm64 => Some("arm64"),
+            Arch::Arm32 => Some("arm32"),
+            Arch::Wasm32 => Some("wasm32"),
+        }
+    }
+}
+
+/// A platform and architecture the extension has libraries for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuildTarget {
+    pub platform: Platform,
+    pub arch: Arch,
+}
+
+impl BuildTarget {
+    /// Rust target triple, `None` for the desktop libraries a plain
+    /// `cargo build` produces in `target/<profile>/`
+    pub fn triple(&self) -> Option<&'static str> {
+        match (self.platform, self.arch) {
+            (Platform::Linux | Platform::Windows, Arch::X86_64) | (Platform::Macos, _) => None,
+            (Platform::Linux, _) => Some("aarch64-unknown-linux-gnu"),
+            (Platform::Windows, _) => Some("aarch64-pc-windows-msvc"),
+            (Platform::Android, Arch::Arm64) => Some("aarch64-linux-android"),
+            (Platform::Android, Arch::Arm32) => Some("armv7-linux-androideabi"),
+            (Platform::Android, Arch::X86_64) => Some("x86_64-linux-android"),
+            (Platform::Android, _) => Some("i686-linux-android"),
+            (Platform::Ios, _) => Some("aarch64-apple-ios"),
+            (Platform::Web, _) => Some("wasm32-unknown-emscripten"),
+        }
+    }
+
+    /// Feature tags of the library entry, e.g. `linux.debug.x86_64`
+    pub fn tags(&self, profile: &str) -> String {
+        match self.arch.tag() {
+            Some(arch) => format!("{}.{}.{}", self.platform.tag(), profile, arch),
+            None => format!("{}.{}", self.platform.tag(), profile),
+        }
+    }
+
+    /// Where cargo puts the library of the crate `name`, relative to its
+    /// target directory
+    pub fn library_path(&self, profile: &str, name: &str) -> String {
+        let file = self.platform.library_file(name);
+        match self.triple() {
+            Some(triple) => format!("{}/{}/{}", triple, profile, file),
+            None => format!("{}/{}", profile, file),
+        }
+    }
+}
+
+/// A platform passed to `--platforms`, optionally narrowed to one
+/// architecture: `android` or `android.arm64`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlatformSpec {
+    pub platform: Platform,
+    pub arch: Option<Arch>,
+}
+
+impl PlatformSpec {
+    /// Every target the spec stands for
+    pub fn targets(&self) -> Vec<BuildTarget> {
+        let arches = match self.arch {
+            Some(arch) => vec![arch],
+            None => self.platform.default_architectures().to_vec(),
+        };
+
+        arches
+            .into_iter()
+            .map(|arch| BuildTarget {
+                platform: self.platform,
+                arch,
+            })
+            .collect()
+    }
+}
+
+impl FromStr for PlatformSpec {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        let (platform, arch) = match raw.split_once('.') {
+            Some((platform, arch)) => (platform, Some(arch)),
+            None => (raw, None),
+        };
+
+        let platform = Platform::from_str(platform, true).map_err(|_| {
+            format!(
+                "unknown platform '{}' (expected one of: {})",
+                platform,
+                Platform::value_variants()
+                    .iter()
+                    .map(Platform::tag)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        })?;
+        let arch = arch
+            .map(|arch| {
+                Arch::from_str(arch, true)
+                    .ok()
+                    .filter(|arch| platform.architectures().contains(arch))
+                    .ok_or_else(|| {
+                        format!(
+                            "'{}' is not an architecture of {} (expected one of: {})",
+                            arch,
+                            platform.tag(),
+                            platform
+                                .architectures()
+                                .iter()
+                                .map(|arch| arch.tag().unwrap_or("universal"))
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        )
+                    })
+            })
+            .transpose()?;
+
+        Ok(Self { platform, arch })
+    }
+}
+
+impl fmt::Display for PlatformSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.platform.tag())?;
+        match self.arch {
+            Some(arch) => write!(f, ".{}", arch.tag().unwrap_or("universal")),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Platforms used when `--platforms` isn't passed
+pub const DEFAULT_PLATFORMS: &[Platform] = &[Platform::Linux, Platform::Windows, Platform::Macos];
+
+/// What the generated `.gdextension` file lists besides its configuration
+///
+/// Rendered into the `gdextension_libraries`, `gdextension_icons` and
+/// `gdextension_dependencies` template variables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtensionEntries {
+    /// One debug and one release library for each target
+    pub targets: Vec<BuildTarget>,
+    /// Editor icons, by class name
+    pub icons: Vec<(String, String)>,
+    /// Other libraries the extension loads, by crate name, exported along
+    /// with it
+    pub dependencies: Vec<String>,
+}
+
+impl Default for ExtensionEntries {
+    fn default() -> Self {
+        Self::new(&[], Vec::new(), Vec::new())
+    }
+}
+
+impl ExtensionEntries {
+    /// Entries for the platforms passed with `--platforms`, or the default
+    /// desktop platforms when there are none; duplicates are dropped
+    pub fn new(
+        platforms: &[PlatformSpec],
+        icons: Vec<(String, String)>,
+        dependencies: Vec<String>,
+    ) -> Self {
+        let defaults: Vec<PlatformSpec> = DEFAULT_PLATFORMS
+            .iter()
+            .map(|&platform| PlatformSpec {
+                platform,
+                arch: None,
+            })
+            .collect();
+        let platforms = if platforms.is_empty() {
+            &defaults
+        } else {
+            platforms
+        };
+
+        let mut targets = Vec::new();
+        for target in platforms.iter().flat_map(PlatformSpec::targets) {
+            if !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+
+        Self {
+            targets,
+            icons,
+            dependencies,
+        }
+    }
+
+    /// The `[libraries]` entries of the crate `name`, whose target directory
+    /// is `library_dir` (a `res://` path)
+    pub fn libraries(&self, library_dir: &str, name: &str) -> String {
+        let entries = self.entries_by_tags(|target, profile| {
+            format!("\"{}/{}\"", library_dir, target.library_path(profile, name))
+        });
+        aligned(&entries)
+    }
+
+    /// The `[icons]` entries, empty without `--extension-icon`
+    pub fn icons(&self) -> String {
+        let entries: Vec<(String, String)> = self
+            .icons
+            .iter()
+            .map(|(class, path)| (class.clone(), format!("\"{}\"", path)))
+            .collect();
+        aligned(&entries)
+    }
+
+    /// The `[dependencies]` entries, one per library, listing the dependency
+    /// libraries built next to it; empty without `--extension-dependency`
+    pub fn dependencies(&self, library_dir: &str) -> String {
+        if self.dependencies.is_empty() {
+            return String::new();
+        }
+
+        let entries = self.entries_by_tags(|target, profile| {
+            let files: Vec<String> = self
+                .dependencies
+                .iter()
+                .map(|dependency| {
+                    format!(
+                        "\"{}/{}\": \"\"",
+                        library_dir,
+                        target.library_path(profile, dependency)
+                    )
+                })
+                .collect();
+            format!("{{ {} }}", files.join(", "))
+        });
+        aligned(&entries)
+    }
+
+    /// A value for the debug and the release entry of each target
+    fn entries_by_tags(
+        &self,
+        value: impl Fn(&BuildTarget, &str) -> String,
+    ) -> Vec<(String, String)> {
+        self.targets
+            .iter()
+            .flat_map(|target| {
+                ["debug", "release"].map(|profile| (target.tags(profile), value(target, profile)))
+            })
+            .collect()
+    }
+}
+
+/// `key = value` lines with the values lined up, as in the templates
+fn aligned(entries: &[(String, String)]) -> String {
+    let width = entries
+        .iter()
+        .map(|(key, _)| key.len() + 2)
+        .max()
+        .unwrap_or(0);
+
+    entries
+        .iter()
+        .map(|(key, value)| format!("{:width$} {}", format!("{} =", key), value))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_platforms_with_optional_architecture() {
+        let android: PlatformSpec = "android".parse().unwrap();
+        assert_eq!(android.targets().len(), 4);
+
+        let arm: PlatformSpec = "android.arm64".parse().unwrap();
+        assert_eq!(
+            arm.targets(),
+            [BuildTarget {
+                platform: Platform::Android,
+                arch: Arch::Arm64
+            }]
+        );
+        assert_eq!(arm.to_string(), "android.arm64");
+
+        assert!("playstation".parse::<PlatformSpec>().is_err());
+        assert!("web.arm64".parse::<PlatformSpec>().is_err());
+    }
+
+    #[test]
+    fn renders_library_entries_for_each_target() {
+        let platforms = [
+            "linux".parse().unwrap(),
+            "linux.arm64".parse().unwrap(),
+            "android.arm64".parse().unwrap(),
+        ];
+        let entries = ExtensionEntries::new(&platforms, Vec::new(), Vec::new());
+
+        assert_eq!(
+            entries.libraries("res://../target", "demo"),
+            [
+                r#"linux.debug.x86_64 =    "res://../target/debug/libdemo.so""#,
+                r#"linux.release.x86_64 =  "res://../target/release/libdemo.so""#,
+                r#"linux.debug.arm64 =     "res://../target/aarch64-unknown-linux-gnu/debug/libdemo.so""#,
+                r#"linux.release.arm64 =   "res://../target/aarch64-unknown-linux-gnu/release/libdemo.so""#,
+                r#"android.debug.arm64 =   "res://../target/aarch64-linux-android/debug/libdemo.so""#,
+                r#"android.release.arm64 = "res://../target/aarch64-linux-android/release/libdemo.so""#,
+            ]
+            .join("\n")
+        );
+        assert_eq!(entries.icons(), "");
+        assert_eq!(entries.dependencies("res://../target"), "");
+    }
+
+    #[test]
+    fn default_platforms_are_the_desktop_ones() {
+        let entries = ExtensionEntries::default();
+
+        assert_eq!(
+            entries.libraries("res://lib", "demo"),
+            [
+                r#"linux.debug.x86_64 =     "res://lib/debug/libdemo.so""#,
+                r#"linux.release.x86_64 =   "res://lib/release/libdemo.so""#,
+                r#"windows.debug.x86_64 =   "res://lib/debug/demo.dll""#,
+                r#"windows.release.x86_64 = "res://lib/release/demo.dll""#,
+                r#"macos.debug =            "res://lib/debug/libdemo.dylib""#,
+                r#"macos.release =          "res://lib/release/libdemo.dylib""#,
+                r#"macos.debug.arm64 =      "res://lib/debug/libdemo.dylib""#,
+                r#"macos.release.arm64 =    "res://lib/release/libdemo.dylib""#,
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn arm64_desktop_libraries_are_only_listed_when_asked_for() {
+        let linux = ExtensionEntries::new(&["linux".parse().unwrap()], Vec::new(), Vec::new());
+        assert!(!linux.libraries("res://lib", "demo").contains("arm64"));
+
+        let arm = ExtensionEntries::new(&["linux.arm64".parse().unwrap()], Vec::new(), Vec::new());
+        assert_eq!(arm.libraries("res://lib", "demo").lines().count(), 2);
+    }
+
+    #[test]
+    fn renders_icons_and_dependencies() {
+        let entries = ExtensionEntries::new(
+            &["web".parse().unwrap()],
+            vec![("Player".to_string(), "res://icons/player.svg".to_string())],
+            vec!["physics".to_string()],
+        );
+
+        assert_eq!(entries.icons(), r#"Player = "res://icons/player.svg""#);
+        assert_eq!(
+            entries.dependencies("res://../target"),
+            [
+                r#"web.debug.wasm32 =   { "res://../target/wasm32-unknown-emscripten/debug/physics.wasm": "" }"#,
+                r#"web.release.wasm32 = { "res://../target/wasm32-unknown-emscripten/release/physics.wasm": "" }"#,
+            ]
+            .join("\n")
+        );
+    }
+}
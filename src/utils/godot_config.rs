@@ -0,0 +1,831 @@
+// Godot's ConfigFile text format
+//
+// Used by `project.godot`, `.gdextension` and `.cfg` files: `[section]`
+// headers followed by `key=value` entries whose values are written in the
+// Variant text format, e.g. `PackedStringArray("4.3", "Mobile")`. Comments
+// start with `;` or `#`. Scenes and resources use the same syntax with
+// attributes in their headers, e.g. `[node name="Main" type="Node"]`.
+//
+// Parsing keeps the original text of every line, so a parsed file is
+// written back byte for byte.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// A value in Godot's Variant text format
+#[derive(Debug, Clone, PartialEq)]
+pub enum Variant {
+    /// `null`
+    Nil,
+    Bool(bool),
+    Int(i64),
+    /// `1.5`, `inf`, `inf_neg`, `nan`
+    Float(f64),
+    /// `"text"`
+    String(String),
+    /// `&"name"`
+    StringName(String),
+    /// `^"path/to/node"`
+    NodePath(String),
+    /// `[1, "two"]`
+    Array(Vec<Variant>),
+    /// `{ "key": value }`, in file order
+    Dictionary(Vec<(Variant, Variant)>),
+    /// Any other type, e.g. `Vector2(1, 2)`, `PackedStringArray("a")`,
+    /// `ExtResource("1_x4k2p")` or `Array[int]([1, 2])`
+    Constructor {
+        name: String,
+        args: Vec<Variant>,
+    },
+    /// `Object(InputEventKey,"keycode":4194309,...)`, as written for input
+    /// actions
+    Object {
+        class: String,
+        properties: Vec<(String, Variant)>,
+    },
+}
+
+impl Variant {
+    /// The contents of a `String`, `StringName` or `NodePath`
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Variant::String(s) | Variant::StringName(s) | Variant::NodePath(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// The strings of a `PackedStringArray` or of an array of strings
+    pub fn as_strings(&self) -> Option<Vec<&str>> {
+        let items = match self {
+            Variant::Array(items) => items,
+            Variant::Constructor { name, args } if name == "PackedStringArray" => args,
+            _ => return None,
+        };
+        items.iter().map(Variant::as_str).collect()
+    }
+}
+
+impl FromStr for Variant {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor::new(s);
+        let value = cursor.value()?;
+        cursor.skip_whitespace();
+        if !cursor.at_end() {
+            return Err(cursor.error("unexpected text after the value"));
+        }
+        Ok(value)
+    }
+}
+
+/// Writes the value the way Godot does
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Variant::Nil => f.write_str("null"),
+            Variant::Bool(b) => write!(f, "{}", b),
+            Variant::Int(i) => write!(f, "{}", i),
+            Variant::Float(x) if x.is_nan() => f.write_str("nan"),
+            Variant::Float(x) if x.is_infinite() => {
+                f.write_str(if *x > 0.0 { "inf" } else { "inf_neg" })
+            }
+            Variant::Float(x) => write_float(f, *x),
+            Variant::String(s) => write_quoted(f, s),
+            Variant::StringName(s) => {
+                f.write_str("&")?;
+                write_quoted(f, s)
+            }
+            Variant::NodePath(s) => {
+                f.write_str("^")?;
+                write_quoted(f, s)
+            }
+            Variant::Array(items) => {
+                f.write_str("[")?;
+                write_separated(f, items, ", ")?;
+                f.write_str("]")
+            }
+            Variant::Dictionary(entries) if entries.is_empty() => f.write_str("{}"),
+            Variant::Dictionary(entries) => {
+                f.write_str("{\n")?;
+                for (index, (key, value)) in entries.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(",\n")?;
+                    }
+                    write!(f, "{}: {}", key, value)?;
+                }
+                f.write_str("\n}")
+            }
+            Variant::Constructor { name, args } => {
+                write!(f, "{}(", name)?;
+                write_separated(f, args, ", ")?;
+                f.write_str(")")
+            }
+            Variant::Object { class, properties } => {
+                write!(f, "Object({}", class)?;
+                for (name, value) in properties {
+                    f.write_str(",")?;
+                    write_quoted(f, name)?;
+                    write!(f, ":{}", value)?;
+                }
+                f.write_str(")")
+            }
+        }
+    }
+}
+
+/// Writes a finite float like Godot's `rtos_fix`: the shortest digits that
+/// read back the same, in C's `%g` notation (`1e+20`, `1e-05`), with `.0`
+/// added to whole numbers
+fn write_float(f: &mut fmt::Formatter<'_>, x: f64) -> fmt::Result {
+    // Godot never writes `-0`, so it doesn't show up in diffs
+    if x == 0.0 {
+        return f.write_str("0.0");
+    }
+
+    // Shortest round-trip digits, e.g. "1.5e20"
+    let scientific = format!("{:e}", x);
+    let (mantissa, exponent) = scientific
+        .split_once('e')
+        .expect("`{:e}` always writes an exponent");
+    let exponent: i32 = exponent.parse().expect("`{:e}` writes an integer exponent");
+
+    // As `%.17g`, enough digits for any double
+    if (-4..17).contains(&exponent) {
+        let fixed = format!("{}", x);
+        if fixed.contains('.') {
+            f.write_str(&fixed)
+        } else {
+            write!(f, "{}.0", fixed)
+        }
+    } else {
+        let sign = if exponent < 0 { '-' } else { '+' };
+        write!(f, "{}e{}{:02}", mantissa, sign, exponent.abs())
+    }
+}
+
+fn write_quoted(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in s.chars() {
+        match c {
+            '\\' => f.write_str("\\\\")?,
+            '"' => f.write_str("\\\"")?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    f.write_str("\"")
+}
+
+fn write_separated(f: &mut fmt::Formatter<'_>, items: &[Variant], separator: &str) -> fmt::Result {
+    for (index, item) in items.iter().enumerate() {
+        if index > 0 {
+            f.write_str(separator)?;
+        }
+        write!(f, "{}", item)?;
+    }
+    Ok(())
+}
+
+/// Why a file or value could not be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line the error was found on
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A parsed `project.godot`, `.gdextension` or `.cfg` file
+///
+/// Displaying it gives back the parsed text unchanged.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigFile {
+    /// Always starts with the unnamed section holding the entries written
+    /// before the first header, like `config_version=5`
+    sections: Vec<Section>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Section {
+    name: String,
+    /// The header line as parsed, `None` for the unnamed section
+    header: Option<String>,
+    lines: Vec<Line>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Line {
+    /// Blank line or comment
+    Trivia(String),
+    Entry {
+        key: String,
+        value: Variant,
+        /// The entry as parsed
+        raw: String,
+    },
+}
+
+impl ConfigFile {
+    /// Keys of the entries in `section`, in file order
+    pub fn keys(&self, section: &str) -> Vec<&str> {
+        self.section(section)
+            .map(|section| {
+                section
+                    .lines
+                    .iter()
+                    .filter_map(|line| match line {
+                        Line::Entry { key, .. } => Some(key.as_str()),
+                        Line::Trivia(_) => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Value of `key` in `section`
+    ///
+    /// Entries written before the first header, like `config_version`, are
+    /// in the `""` section.
+    pub fn get(&self, section: &str, key: &str) -> Option<&Variant> {
+        self.section(section)?
+            .lines
+            .iter()
+            .find_map(|line| match line {
+                Line::Entry { key: k, value, .. } if k == key => Some(value),
+                _ => None,
+            })
+    }
+
+    fn section(&self, name: &str) -> Option<&Section> {
+        self.sections.iter().find(|section| section.name == name)
+    }
+}
+
+impl FromStr for ConfigFile {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut cursor = Cursor::new(s);
+        let mut sections = vec![Section {
+            name: String::new(),
+            header: None,
+            lines: Vec::new(),
+        }];
+
+        while !cursor.at_end() {
+            let start = cursor.pos;
+            cursor.skip_spaces();
+
+            match cursor.peek() {
+                None | Some('\n' | '\r' | ';' | '#') => {
+                    cursor.skip_line();
+                    let lines = &mut sections.last_mut().expect("the unnamed section").lines;
+                    lines.push(Line::Trivia(s[start..cursor.pos].to_string()));
+                }
+                Some('[') => {
+                    let name = cursor.header()?;
+                    cursor.end_of_line()?;
+                    sections.push(Section {
+                        name,
+                        header: Some(s[start..cursor.pos].to_string()),
+                        lines: Vec::new(),
+                    });
+                }
+                Some(_) => {
+                    let key = cursor.key()?;
+                    cursor.skip_spaces();
+                    let value = cursor.value()?;
+                    cursor.end_of_line()?;
+
+                    let lines = &mut sections.last_mut().expect("the unnamed section").lines;
+                    lines.push(Line::Entry {
+                        key,
+                        value,
+                        raw: s[start..cursor.pos].to_string(),
+                    });
+                }
+            }
+        }
+
+        Ok(Self { sections })
+    }
+}
+
+impl fmt::Display for ConfigFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for section in &self.sections {
+            if let Some(header) = &section.header {
+                f.write_str(header)?;
+            }
+            for line in &section.lines {
+                match line {
+                    Line::Trivia(text) | Line::Entry { raw: text, .. } => f.write_str(text)?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Reads through the text of a file or value
+struct Cursor<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(text: &'a str) -> Self {
+        Self { text, pos: 0 }
+    }
+
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        self.error_at(self.pos, message)
+    }
+
+    fn error_at(&self, pos: usize, message: impl Into<String>) -> ParseError {
+        ParseError {
+            line: self.text[..pos].matches('\n').count() + 1,
+            message: message.into(),
+        }
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos >= self.text.len()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        let found = self.peek() == Some(expected);
+        if found {
+            self.pos += expected.len_utf8();
+        }
+        found
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        if self.eat(expected) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected '{}'", expected)))
+        }
+    }
+
+    /// Skips spaces and tabs, staying on the current line
+    fn skip_spaces(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t')) {
+            self.pos += 1;
+        }
+    }
+
+    /// Skips whitespace, newlines and comments
+    fn skip_whitespace(&mut self) {
+        loop {
+            match self.peek() {
+                Some(c) if c.is_whitespace() => {
+                    self.next();
+                }
+                Some(';' | '#') => {
+                    while !matches!(self.peek(), None | Some('\n')) {
+                        self.next();
+                    }
+                }
+                _ => break,
+            }
+        }
+    }
+
+    /// Moves past the next newline, or to the end of the text
+    fn skip_line(&mut self) {
+        match self.text[self.pos..].find('\n') {
+            Some(offset) => self.pos += offset + 1,
+            None => self.pos = self.text.len(),
+        }
+    }
+
+    /// Allows trailing spaces and a comment, then moves past the newline
+    fn end_of_line(&mut self) -> Result<(), ParseError> {
+        self.skip_spaces();
+        match self.peek() {
+            None | Some('\n' | '\r' | ';' | '#') => {
+                self.skip_line();
+                Ok(())
+            }
+            Some(_) => Err(self.error("unexpected text at the end of the line")),
+        }
+    }
+
+    /// `[name]` or `[tag attribute=value ...]`, returning what is between the
+    /// brackets
+    fn header(&mut self) -> Result<String, ParseError> {
+        self.expect('[')?;
+        let start = self.pos;
+        loop {
+            match self.peek() {
+                Some(']') => break,
+                Some('"') => {
+                    self.string()?;
+                }
+                None | Some('\n') => return Err(self.error("unterminated section header")),
+                Some(_) => {
+                    self.next();
+                }
+            }
+        }
+        let name = self.text[start..self.pos].trim().to_string();
+        self.expect(']')?;
+        Ok(name)
+    }
+
+    /// The key of an entry, bare or quoted, and the `=` after it
+    fn key(&mut self) -> Result<String, ParseError> {
+        let key = if self.peek() == Some('"') {
+            let key = self.string()?;
+            self.skip_spaces();
+            key
+        } else {
+            let start = self.pos;
+            while !matches!(self.peek(), None | Some('=' | '\n')) {
+                self.next();
+            }
+            self.text[start..self.pos].trim_end().to_string()
+        };
+
+        if key.is_empty() || !self.eat('=') {
+            return Err(self.error("expected 'key=value'"));
+        }
+        Ok(key)
+    }
+
+    fn value(&mut self) -> Result<Variant, ParseError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('"') => Ok(Variant::String(self.string()?)),
+            Some('&') => {
+                self.next();
+                Ok(Variant::StringName(self.string()?))
+            }
+            Some('^') => {
+                self.next();
+                Ok(Variant::NodePath(self.string()?))
+            }
+            Some('[') => {
+                self.next();
+                Ok(Variant::Array(self.list(']')?))
+            }
+            Some('{') => {
+                self.next();
+                self.dictionary()
+            }
+            Some(c) if c.is_ascii_digit() || matches!(c, '-' | '+' | '.') => self.number(),
+            Some(c) if c.is_alphabetic() || c == '_' => self.identifier_value(),
+            Some(c) => Err(self.error(format!("unexpected '{}'", c))),
+            None => Err(self.error("expected a value")),
+        }
+    }
+
+    /// Values separated by commas up to `close`, which is consumed
+    fn list(&mut self, close: char) -> Result<Vec<Variant>, ParseError> {
+        let mut items = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.eat(close) {
+                return Ok(items);
+            }
+            if !items.is_empty() {
+                self.expect(',')?;
+                self.skip_whitespace();
+                // Godot accepts a trailing comma
+                if self.eat(close) {
+                    return Ok(items);
+                }
+            }
+            items.push(self.value()?);
+        }
+    }
+
+    fn dictionary(&mut self) -> Result<Variant, ParseError> {
+        let mut entries = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.eat('}') {
+                return Ok(Variant::Dictionary(entries));
+            }
+            if !entries.is_empty() {
+                self.expect(',')?;
+                self.skip_whitespace();
+                if self.eat('}') {
+                    return Ok(Variant::Dictionary(entries));
+                }
+            }
+            let key = self.value()?;
+            self.skip_whitespace();
+            self.expect(':')?;
+            entries.push((key, self.value()?));
+        }
+    }
+
+    /// A `"..."` string, unescaped
+    fn string(&mut self) -> Result<String, ParseError> {
+        let start = self.pos;
+        self.expect('"')?;
+        let mut string = String::new();
+        loop {
+            match self.next() {
+                None => return Err(self.error_at(start, "unterminated string")),
+                Some('"') => return Ok(string),
+                Some('\\') => match self.next() {
+                    Some('n') => string.push('\n'),
+                    Some('t') => string.push('\t'),
+                    Some('r') => string.push('\r'),
+                    Some('b') => string.push('\u{8}'),
+                    Some('f') => string.push('\u{c}'),
+                    Some('u') => {
+                        let start = self.pos;
+                        for _ in 0..4 {
+                            self.next();
+                        }
+                        let c = u32::from_str_radix(&self.text[start..self.pos], 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .ok_or_else(|| self.error("invalid \\u escape"))?;
+                        string.push(c);
+                    }
+                    Some(c) => string.push(c),
+                    None => return Err(self.error_at(start, "unterminated string")),
+                },
+                Some(c) => string.push(c),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Variant, ParseError> {
+        let start = self.pos;
+        self.next();
+        while let Some(c) = self.peek() {
+            let exponent_sign =
+                matches!(c, '-' | '+') && self.text[..self.pos].ends_with(['e', 'E']);
+            if !(c.is_ascii_alphanumeric() || c == '.' || exponent_sign) {
+                break;
+            }
+            self.next();
+        }
+
+        let text = &self.text[start..self.pos];
+        if text == "-inf" {
+            return Ok(Variant::Float(f64::NEG_INFINITY));
+        }
+        let parsed = if text.contains(['.', 'e', 'E']) {
+            text.parse().ok().map(Variant::Float)
+        } else {
+            text.parse().ok().map(Variant::Int)
+        };
+        parsed.ok_or_else(|| self.error(format!("invalid number '{}'", text)))
+    }
+
+    /// Keywords, `Type(...)` constructors and `Object(...)`
+    fn identifier_value(&mut self) -> Result<Variant, ParseError> {
+        let name = self.identifier();
+        match name.as_str() {
+            "null" | "nil" => return Ok(Variant::Nil),
+            "true" => return Ok(Variant::Bool(true)),
+            "false" => return Ok(Variant::Bool(false)),
+            "inf" => return Ok(Variant::Float(f64::INFINITY)),
+            "inf_neg" => return Ok(Variant::Float(f64::NEG_INFINITY)),
+            "nan" => return Ok(Variant::Float(f64::NAN)),
+            _ => {}
+        }
+
+        // Typed collections, e.g. `Array[int]` or `Dictionary[String, int]`
+        let mut name = name;
+        if self.peek() == Some('[') {
+            let start = self.pos;
+            while !matches!(self.peek(), None | Some(']')) {
+                self.next();
+            }
+            self.expect(']')?;
+            name.push_str(&self.text[start..self.pos]);
+        }
+
+        self.skip_spaces();
+        if !self.eat('(') {
+            return Err(self.error(format!("unknown value '{}'", name)));
+        }
+        if name == "Object" {
+            return self.object();
+        }
+
+        Ok(Variant::Constructor {
+            name,
+            args: self.list(')')?,
+        })
+    }
+
+    fn identifier(&mut self) -> String {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_alphanumeric() || c == '_') {
+            self.next();
+        }
+        self.text[start..self.pos].to_string()
+    }
+
+    /// The rest of `Object(Class,"property":value,...)`
+    fn object(&mut self) -> Result<Variant, ParseError> {
+        self.skip_whitespace();
+        let class = self.identifier();
+        if class.is_empty() {
+            return Err(self.error("expected the class of the object"));
+        }
+
+        let mut properties = Vec::new();
+        loop {
+            self.skip_whitespace();
+            if self.eat(')') {
+                return Ok(Variant::Object { class, properties });
+            }
+            self.expect(',')?;
+            self.skip_whitespace();
+            let name = self.string()?;
+            self.skip_whitespace();
+            self.expect(':')?;
+            properties.push((name, self.value()?));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::{GeneratorInput, base_context};
+    use crate::utils::gdextension::{ExtensionEntries, PlatformSpec};
+    use crate::utils::layout::ProjectLayout;
+    use crate::utils::template_parser::{TemplateSource, plan_template_filtered};
+    use crate::utils::uid_generator::UidGenerator;
+    use std::path::Path;
+
+    const PROJECT: &str = r#"; Engine configuration file.
+; It's best edited using the editor UI and not directly,
+
+config_version=5
+
+[application]
+
+config/name="Dodge the \"Creeps\"!"
+config/features=PackedStringArray("4.3", "Mobile")
+
+[autoload]
+
+Globals="*res://globals.gd"
+
+[display]
+
+window/size/viewport_width=480
+window/stretch/scale=1.5
+
+[input]
+
+move_left={
+"deadzone": 0.5,
+"events": [Object(InputEventKey,"resource_local_to_scene":false,"keycode":0,"physical_keycode":65,"unicode":97,"echo":false,"script":null)
+, Object(InputEventJoypadMotion,"axis":0,"axis_value":-1.0,"script":null)
+]
+}
+
+[rendering]
+
+environment/defaults/default_clear_color=Color(0.3, 0.3, 0.3, 1)
+textures/canvas_textures/default_texture_filter=0
+"#;
+
+    fn is_config_file(path: &Path) -> bool {
+        path.extension().is_some_and(|ext| {
+            ["godot", "gdextension", "cfg", "tscn"].contains(&&*ext.to_string_lossy())
+        })
+    }
+
+    #[test]
+    fn round_trips_the_embedded_templates() {
+        let mut input = GeneratorInput::for_tests("my_game", ProjectLayout::Workspace);
+        input.extension = ExtensionEntries::new(
+            &[
+                "linux".parse::<PlatformSpec>().unwrap(),
+                "android.arm64".parse().unwrap(),
+            ],
+            vec![("Player".to_string(), "res://icons/player.svg".to_string())],
+            vec!["game_logic".to_string()],
+        );
+        let context = base_context(&input);
+
+        let mut checked = 0;
+        for dir in crate::TEMPLATES.dirs() {
+            let files = plan_template_filtered(
+                &TemplateSource::Embedded(dir),
+                &context,
+                UidGenerator::Seeded(7),
+                is_config_file,
+            )
+            .unwrap();
+
+            for file in files {
+                let text = String::from_utf8(file.contents).unwrap();
+                let config: ConfigFile = text
+                    .parse()
+                    .unwrap_or_else(|e| panic!("{}: {}", file.path.display(), e));
+                assert_eq!(config.to_string(), text, "{}", file.path.display());
+                checked += 1;
+            }
+        }
+        assert!(checked >= 6);
+    }
+
+    #[test]
+    fn reads_sections_and_values() {
+        let config: ConfigFile = PROJECT.parse().unwrap();
+        assert_eq!(config.to_string(), PROJECT);
+
+        assert_eq!(config.get("", "config_version"), Some(&Variant::Int(5)));
+        assert_eq!(
+            config
+                .get("application", "config/name")
+                .and_then(Variant::as_str),
+            Some("Dodge the \"Creeps\"!")
+        );
+        assert_eq!(
+            config
+                .get("application", "config/features")
+                .and_then(Variant::as_strings),
+            Some(vec!["4.3", "Mobile"])
+        );
+        assert_eq!(
+            config.get("display", "window/stretch/scale"),
+            Some(&Variant::Float(1.5))
+        );
+        assert_eq!(config.keys("autoload"), ["Globals"]);
+
+        let Some(Variant::Dictionary(action)) = config.get("input", "move_left") else {
+            panic!("move_left is not a dictionary");
+        };
+        let Variant::Array(events) = &action[1].1 else {
+            panic!("events is not an array");
+        };
+        assert!(matches!(
+            &events[1],
+            Variant::Object { class, properties }
+                if class == "InputEventJoypadMotion" && properties[1].1 == Variant::Float(-1.0)
+        ));
+    }
+
+    #[test]
+    fn writes_values_back_the_way_godot_does() {
+        for text in [
+            "null",
+            "-12",
+            "1.0",
+            "-0.25",
+            "100.0",
+            "1e+20",
+            "-1.5e-05",
+            "0.0001",
+            "1.7976931348623157e+308",
+            "inf_neg",
+            "\"say \\\"hi\\\"\"",
+            "&\"jump\"",
+            "^\"../Player\"",
+            "[1, \"two\", [true]]",
+            "{\n\"a\": 1,\n\"b\": Vector2(1, -2.5)\n}",
+            "PackedStringArray(\"4.4\", \"Forward Plus\")",
+            "Array[int]([1, 2])",
+            "Object(InputEventKey,\"keycode\":65,\"script\":null)",
+        ] {
+            let value: Variant = text.parse().unwrap();
+            assert_eq!(value.to_string(), text);
+        }
+        assert_eq!(Variant::Float(-0.0).to_string(), "0.0");
+
+        assert!("Vector2(1, 2".parse::<Variant>().is_err());
+        assert!("unknown".parse::<Variant>().is_err());
+        assert_eq!(
+            "[application]\nconfig/name=\"x\n"
+                .parse::<ConfigFile>()
+                .unwrap_err()
+                .line,
+            2
+        );
+    }
+}
@@ -0,0 +1,310 @@
+use clap::ValueEnum;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::utils::engine::{EngineVersion, RenderingMethod};
+use crate::utils::godot_config::{ConfigFile, ParseError, Variant};
+
+/// File marking the root of a Godot project
+pub const PROJECT_FILE: &str = "project.godot";
+
+/// Extensions the editor loads on startup, one `res://` path per line
+const EXTENSION_LIST: &str = ".godot/extension_list.cfg";
+
+/// Settings of an existing Godot project read from its `project.godot`
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GodotProject {
+    /// `application/config/name`
+    pub name: Option<String>,
+    /// `application/config/features`, e.g. `["4.3", "Forward Plus"]`
+    pub features: Vec<String>,
+    /// `rendering/renderer/rendering_method`
+    pub rendering_method: Option<String>,
+}
+
+impl GodotProject {
+    /// Whether `dir` holds a Godot project
+    pub fn exists_in(dir: &Path) -> bool {
+        dir.join(PROJECT_FILE).is_file()
+    }
+
+    /// Reads the `project.godot` in `dir`
+    pub fn load(dir: &Path) -> io::Result<Self> {
+        let path = dir.join(PROJECT_FILE);
+        Self::parse(&fs::read_to_string(&path)?).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Could not parse {}: {}", path.display(), e),
+            )
+        })
+    }
+
+    /// Picks the settings godust needs out of a `project.godot`
+    pub fn parse(contents: &str) -> Result<Self, ParseError> {
+        let config: ConfigFile = contents.parse()?;
+        let string = |section: &str, key: &str| {
+            config
+                .get(section, key)
+                .and_then(Variant::as_str)
+                .map(str::to_string)
+        };
+
+        Ok(Self {
+            name: string("application", "config/name"),
+            features: config
+                .get("application", "config/features")
+                .and_then(Variant::as_strings)
+                .map(|tags| tags.into_iter().map(str::to_string).collect())
+                .unwrap_or_default(),
+            rendering_method: string("rendering", "renderer/rendering_method"),
+        })
+    }
+
+    /// Crate name derived from the project name, e.g. `dodge_the_creeps`
+    /// for "Dodge the Creeps!"
+    pub fn crate_name(&self) -> Option<String> {
+        let name = self.name.as_deref()?;
+        let words: Vec<String> = name
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_ascii_lowercase)
+            .collect();
+
+        (!words.is_empty()).then(|| words.join("_"))
+    }
+
+    /// Engine version from the `4.x` feature tag
+    pub fn engine_version(&self) -> Option<EngineVersion> {
+        self.features.iter().find_map(|tag| tag.parse().ok())
+    }
+
+    /// Renderer the project uses, Godot's default Forward+ when it isn't set
+    pub fn rendering_method(&self) -> RenderingMethod {
+        let methods = RenderingMethod::value_variants().iter().copied();
+        let by_identifier = self
+            .rendering_method
+            .as_deref()
+            .and_then(|id| methods.clone().find(|m| m.identifier() == id));
+        let by_feature = || {
+            methods
+                .clone()
+                .find(|m| self.features.iter().any(|tag| tag == m.display_name()))
+        };
+
+        by_identifier.or_else(by_feature).unwrap_or_default()
+    }
+}
+
+/// Finds the Godot project belonging to the crate in `dir`
+///
+/// Looks at `dir` and its two parents, nearest first, and at their
+/// subdirectories up to two levels deep, which covers every layout godust
+/// generates.
+pub fn find_near(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors().take(3).find_map(|ancestor| {
+        if GodotProject::exists_in(ancestor) {
+            return Some(ancestor.to_path_buf());
+        }
+
+        WalkDir::new(ancestor)
+            .min_depth(1)
+            .max_depth(2)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|entry| {
+                let name = entry.file_name().to_string_lossy();
+                entry.file_type().is_dir() && !name.starts_with('.') && name != "target"
+            })
+            .filter_map(Result::ok)
+            .find(|entry| GodotProject::exists_in(entry.path()))
+            .map(|entry| entry.into_path())
+    })
+}
+
+/// Adds `res_path` to the project's `.godot/extension_list.cfg`
+///
+/// Returns `false` without writing anything when the project was never
+/// opened in the editor: Godot lists every `.gdextension` on its first
+/// import anyway.
+pub fn register_extension(godot_dir: &Path, res_path: &str) -> io::Result<bool> {
+    let list = godot_dir.join(EXTENSION_LIST);
+    if !list.parent().is_some_and(Path::is_dir) {
+        return Ok(false);
+    }
+
+    let mut contents = match fs::read_to_string(&list) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e),
+    };
+    if contents.lines().any(|line| line.trim() == res_path) {
+        return Ok(true);
+    }
+
+    if !contents.is_empty() && !contents.ends_with('\n') {
+        contents.push('\n');
+    }
+    contents.push_str(res_path);
+    contents.push('\n');
+    fs::write(list, contents)?;
+
+    Ok(true)
+}
+
+/// Every `.gdextension` file of the Godot project in `godot_dir`, skipping
+/// hidden directories and build output
+pub fn find_extensions(godot_dir: &Path) -> Vec<PathBuf> {
+    WalkDir::new(godot_dir)
+        .max_depth(3)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            entry.depth() == 0
+                || !entry.file_type().is_dir()
+                || (!name.starts_with('.') && name != "target")
+        })
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            entry.file_type().is_file()
+                && entry
+                    .path()
+                    .extension()
+                    .is_some_and(|ext| ext == "gdextension")
+        })
+        .map(|entry| entry.into_path())
+        .collect()
+}
+
+/// The `[libraries]` of a `.gdextension` file: each entry's feature tags
+/// (e.g. `linux.debug.x86_64`) and library path
+pub fn extension_libraries(contents: &str) -> Result<Vec<(String, String)>, ParseError> {
+    let config: ConfigFile = contents.parse()?;
+
+    Ok(config
+        .keys("libraries")
+        .into_iter()
+        .filter_map(|tags| {
+            let path = config.get("libraries", tags)?.as_str()?;
+            Some((tags.to_string(), path.to_string()))
+        })
+        .collect())
+}
+
+/// The library Godot loads when running with `features`
+///
+/// Like Godot, picks the entry whose tags are all among `features` and
+/// that has the most tags; the first one wins a tie.
+pub fn library_for<'a>(libraries: &'a [(String, String)], features: &[&str]) -> Option<&'a str> {
+    let mut best: Option<(usize, &str)> = None;
+
+    for (tags, path) in libraries {
+        let tags: Vec<&str> = tags.split('.').collect();
+        if !tags.iter().all(|tag| features.contains(tag)) {
+            continue;
+        }
+        if best.is_none_or(|(count, _)| tags.len() > count) {
+            best = Some((tags.len(), path));
+        }
+    }
+
+    best.map(|(_, path)| path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROJECT: &str = r#"; Engine configuration file.
+config_version=5
+
+[application]
+
+config/name="Dodge the \"Creeps\"!"
+run/main_scene="res://main.tscn"
+config/features=PackedStringArray("4.3", "Mobile")
+
+[rendering]
+
+renderer/rendering_method="mobile"
+"#;
+
+    #[test]
+    fn reads_name_version_and_renderer() {
+        let project = GodotProject::parse(PROJECT).unwrap();
+
+        assert_eq!(project.name.as_deref(), Some("Dodge the \"Creeps\"!"));
+        assert_eq!(project.crate_name().as_deref(), Some("dodge_the_creeps"));
+        assert_eq!(project.engine_version(), Some("4.3".parse().unwrap()));
+        assert_eq!(project.rendering_method(), RenderingMethod::Mobile);
+    }
+
+    #[test]
+    fn renderer_falls_back_to_features_then_forward_plus() {
+        let compatibility = GodotProject::parse(
+            "[application]\nconfig/features=PackedStringArray(\"4.4\", \"GL Compatibility\")\n",
+        )
+        .unwrap();
+        assert_eq!(
+            compatibility.rendering_method(),
+            RenderingMethod::GlCompatibility
+        );
+        assert_eq!(
+            GodotProject::default().rendering_method(),
+            RenderingMethod::ForwardPlus
+        );
+    }
+
+    #[test]
+    fn picks_the_most_specific_library() {
+        let libraries = extension_libraries(
+            r#"[configuration]
+entry_symbol = "gdext_rust_init"
+
+[libraries]
+linux.debug.x86_64 =     "res://../demo_core/target/debug/libdemo.so"
+macos.debug =            "res://../demo_core/target/debug/libdemo.dylib"
+macos.debug.arm64 =      "res://../demo_core/target/debug/libdemo_arm.dylib"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(libraries.len(), 3);
+        assert_eq!(
+            library_for(&libraries, &["linux", "debug", "x86_64"]),
+            Some("res://../demo_core/target/debug/libdemo.so")
+        );
+        assert_eq!(
+            library_for(&libraries, &["macos", "debug", "arm64"]),
+            Some("res://../demo_core/target/debug/libdemo_arm.dylib")
+        );
+        assert_eq!(
+            library_for(&libraries, &["macos", "debug", "x86_64"]),
+            Some("res://../demo_core/target/debug/libdemo.dylib")
+        );
+        assert_eq!(
+            library_for(&libraries, &["linux", "release", "x86_64"]),
+            None
+        );
+    }
+
+    #[test]
+    fn registers_each_extension_once() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path();
+
+        assert!(!register_extension(dir, "res://game.gdextension").unwrap());
+
+        fs::create_dir(dir.join(".godot")).unwrap();
+        fs::write(dir.join(EXTENSION_LIST), "res://addons/other.gdextension").unwrap();
+        assert!(register_extension(dir, "res://game.gdextension").unwrap());
+        assert!(register_extension(dir, "res://game.gdextension").unwrap());
+
+        assert_eq!(
+            fs::read_to_string(dir.join(EXTENSION_LIST)).unwrap(),
+            "res://addons/other.gdextension\nres://game.gdextension\n"
+        );
+    }
+}
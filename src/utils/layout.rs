@@ -0,0 +1,58 @@
+use clap::ValueEnum;
+use std::fmt;
+
+/// Library crate generated next to the GDExtension crate in a workspace
+pub const GAME_LOGIC_CRATE: &str = "game_logic";
+
+/// How the generated Rust code is organised
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum ProjectLayout {
+    /// A single GDExtension crate next to the Godot project
+    #[default]
+    Standalone,
+    /// A Cargo workspace: the GDExtension crate, a `game_logic` library and tool crates
+    Workspace,
+}
+
+impl ProjectLayout {
+    /// Name passed to `--layout` and exposed to templates as `layout`
+    pub fn identifier(&self) -> &'static str {
+        match self {
+            ProjectLayout::Standalone => "standalone",
+            ProjectLayout::Workspace => "workspace",
+        }
+    }
+
+    /// Crates the layout adds to the template's own, by name
+    pub fn extra_crates(&self, tools: &[String]) -> Vec<String> {
+        match self {
+            ProjectLayout::Standalone => Vec::new(),
+            ProjectLayout::Workspace => [GAME_LOGIC_CRATE.to_string()]
+                .into_iter()
+                .chain(tools.iter().cloned())
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for ProjectLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.identifier())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn workspace_adds_game_logic_and_tools() {
+        let tools = vec!["level_editor".to_string()];
+
+        assert!(ProjectLayout::Standalone.extra_crates(&tools).is_empty());
+        assert_eq!(
+            ProjectLayout::Workspace.extra_crates(&tools),
+            ["game_logic", "level_editor"]
+        );
+    }
+}
@@ -0,0 +1,473 @@
+use crate::utils::naming::ProjectNames;
+
+/// Rust strict and reserved keywords, across all editions
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Package names Cargo refuses or that break on some platforms
+const RESERVED_CRATE_NAMES: &[&str] = &[
+    // Rust's built-in libraries
+    "alloc",
+    "core",
+    "proc_macro",
+    "std",
+    "test",
+    // Windows reserved file names
+    "aux",
+    "con",
+    "nul",
+    "prn",
+    "com1",
+    "com2",
+    "com3",
+    "com4",
+    "com5",
+    "com6",
+    "com7",
+    "com8",
+    "com9",
+    "lpt1",
+    "lpt2",
+    "lpt3",
+    "lpt4",
+    "lpt5",
+    "lpt6",
+    "lpt7",
+    "lpt8",
+    "lpt9",
+    // godot-rust's own crates
+    "gdext",
+    "godot",
+    "godot_bindings",
+    "godot_cell",
+    "godot_codegen",
+    "godot_core",
+    "godot_ffi",
+    "godot_macros",
+];
+
+/// Godot built-in classes and godot-rust prelude types a project struct would shadow
+const GODOT_TYPES: &[&str] = &[
+    // Core classes
+    "Object",
+    "RefCounted",
+    "Resource",
+    "Node",
+    "Node2D",
+    "Node3D",
+    "Control",
+    "CanvasItem",
+    "Viewport",
+    "Window",
+    "SceneTree",
+    "PackedScene",
+    "Engine",
+    "Input",
+    "Os",
+    "Time",
+    "Timer",
+    "Image",
+    "Texture2D",
+    "Mesh",
+    "Material",
+    "Shader",
+    "Script",
+    "Camera2D",
+    "Camera3D",
+    "Sprite2D",
+    "Sprite3D",
+    "Area2D",
+    "Area3D",
+    "CharacterBody2D",
+    "CharacterBody3D",
+    "RigidBody2D",
+    "RigidBody3D",
+    "StaticBody2D",
+    "StaticBody3D",
+    "Label",
+    "Button",
+    "Animation",
+    "AnimationPlayer",
+    "AudioStreamPlayer",
+    "Tween",
+    // godot-rust prelude
+    "Gd",
+    "Base",
+    "Variant",
+    "GString",
+    "StringName",
+    "NodePath",
+    "Array",
+    "Dictionary",
+    "Vector2",
+    "Vector2i",
+    "Vector3",
+    "Vector3i",
+    "Vector4",
+    "Color",
+    "Rect2",
+    "Transform2D",
+    "Transform3D",
+    "Basis",
+    "Quaternion",
+    "Callable",
+    "Signal",
+    "Rid",
+    "Aabb",
+    "Plane",
+    "Projection",
+    "ExtensionLibrary",
+    "GodotClass",
+    "Godot",
+];
+
+/// Longest crate name accepted
+///
+/// The crate name ends up in several path components (`<name>_core/`,
+/// `lib<name>.dylib`, `target/debug/deps/lib<name>-<hash>.so`) and Windows
+/// paths are limited to 260 characters, so this is well below what file
+/// systems allow per component.
+pub const MAX_NAME_LENGTH: usize = 64;
+
+/// A problem found with a project name
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum NameProblem {
+    /// Crate names can't start with a digit
+    LeadingDigit,
+    /// The casing of the name can't be detected
+    UnsupportedCasing,
+    /// The crate name is a Rust keyword
+    RustKeyword(String),
+    /// Cargo rejects the crate name or it is reserved on some platform
+    ReservedCrateName(String),
+    /// The crate name is also the name of one of its dependencies
+    DependencyName(String),
+    /// The crate name is longer than [`MAX_NAME_LENGTH`]
+    TooLong(usize),
+    /// The struct name shadows a Godot class or godot-rust type (warning only)
+    ShadowsGodotType(String),
+}
+
+impl NameProblem {
+    /// Errors stop generation, everything else is a warning
+    pub fn is_error(&self) -> bool {
+        !matches!(self, NameProblem::ShadowsGodotType(_))
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            NameProblem::LeadingDigit => "crate names can't start with a digit".to_string(),
+            NameProblem::UnsupportedCasing => {
+                "use snake_case, kebab-case, PascalCase or camelCase".to_string()
+            }
+            NameProblem::RustKeyword(name) => format!("'{}' is a Rust keyword", name),
+            NameProblem::ReservedCrateName(name) => {
+                format!("'{}' is a reserved crate name", name)
+            }
+            NameProblem::DependencyName(name) => {
+                format!("'{}' is the name of a crate the project depends on", name)
+            }
+            NameProblem::TooLong(length) => format!(
+                "the name is {} characters long, the maximum is {}",
+                length, MAX_NAME_LENGTH
+            ),
+            NameProblem::ShadowsGodotType(name) => format!(
+                "'{}' shadows the Godot/godot-rust type with the same name",
+                name
+            ),
+        }
+    }
+}
+
+/// Result of checking a project name
+#[derive(Debug, Clone)]
+pub struct NameReport {
+    /// Derived names, when the casing could be detected
+    pub names: Option<ProjectNames>,
+    pub problems: Vec<NameProblem>,
+    /// A close name without errors, offered when there are errors
+    pub suggestion: Option<String>,
+}
+
+impl NameReport {
+    pub fn has_errors(&self) -> bool {
+        self.problems.iter().any(NameProblem::is_error)
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &NameProblem> {
+        self.problems.iter().filter(|p| p.is_error())
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &NameProblem> {
+        self.problems.iter().filter(|p| !p.is_error())
+    }
+}
+
+/// Checks a project name given in any casing
+///
+/// `dependencies` are the crates the generated project depends on; the
+/// project crate can't share a name with them.
+pub fn check_project_name(input: &str, dependencies: &[&str]) -> NameReport {
+    if input.starts_with(|c: char| c.is_ascii_digit()) {
+        let suggestion = move_leading_digits(input);
+        return NameReport {
+            names: None,
+            problems: vec![NameProblem::LeadingDigit],
+            suggestion: Some(suggestion),
+        };
+    }
+
+    let Some(names) = ProjectNames::from_input(input) else {
+        return NameReport {
+            names: None,
+            problems: vec![NameProblem::UnsupportedCasing],
+            suggestion: None,
+        };
+    };
+
+    let problems = find_problems(&names, dependencies);
+    let suggestion = problems
+        .iter()
+        .any(NameProblem::is_error)
+        .then(|| suggest(&names.snake, dependencies));
+
+    NameReport {
+        names: Some(names),
+        problems,
+        suggestion,
+    }
+}
+
+fn find_problems(names: &ProjectNames, dependencies: &[&str]) -> Vec<NameProblem> {
+    let mut problems = Vec::new();
+    let snake = names.snake.as_str();
+
+    if RUST_KEYWORDS.contains(&snake) {
+        problems.push(NameProblem::RustKeyword(snake.to_string()));
+    }
+    if RESERVED_CRATE_NAMES.contains(&snake) {
+        problems.push(NameProblem::ReservedCrateName(snake.to_string()));
+    }
+    if dependencies
+        .iter()
+        .any(|dep| dep.replace('-', "_") == snake)
+    {
+        problems.push(NameProblem::DependencyName(snake.to_string()));
+    }
+    if snake.len() > MAX_NAME_LENGTH {
+        problems.push(NameProblem::TooLong(snake.len()));
+    }
+    if GODOT_TYPES
+        .iter()
+        .any(|ty| ty.eq_ignore_ascii_case(&names.pascal))
+    {
+        problems.push(NameProblem::ShadowsGodotType(names.pascal.clone()));
+    }
+
+    problems
+}
+
+/// Checks the name of a new Godot class given in any casing
+///
+/// Returns its derived names; `pascal` is the class name and `snake` the
+/// default module name. Unlike project names, clashing with a Godot class is
+/// an error, since Godot can't register two classes with the same name.
+pub fn check_class_name(input: &str) -> Result<ProjectNames, String> {
+    if input.starts_with(|c: char| c.is_ascii_digit()) {
+        return Err("class names can't start with a digit".to_string());
+    }
+
+    let names =
+        ProjectNames::from_input(input).ok_or_else(|| NameProblem::UnsupportedCasing.message())?;
+
+    if GODOT_TYPES
+        .iter()
+        .any(|ty| ty.eq_ignore_ascii_case(&names.pascal))
+    {
+        return Err(format!(
+            "'{}' is already a Godot class or godot-rust type",
+            input
+        ));
+    }
+
+    Ok(names)
+}
+
+/// Checks a module path such as `actors::player` (or `actors/player`)
+///
+/// Returns its snake_case segments.
+pub fn check_module_path(input: &str) -> Result<Vec<String>, String> {
+    let segments: Vec<&str> = input.split("::").flat_map(|s| s.split('/')).collect();
+
+    for segment in &segments {
+        let valid = segment.starts_with(|c: char| c.is_ascii_lowercase())
+            && segment
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if !valid {
+            return Err(format!(
+                "'{}' is not a snake_case module name (e.g. actors::player)",
+                input
+            ));
+        }
+        if RUST_KEYWORDS.contains(segment) {
+            return Err(NameProblem::RustKeyword(segment.to_string()).message());
+        }
+    }
+
+    Ok(segments.into_iter().map(str::to_string).collect())
+}
+
+/// Builds an alternative snake_case name without errors
+fn suggest(snake: &str, dependencies: &[&str]) -> String {
+    let mut candidate = snake.to_string();
+
+    if candidate.len() > MAX_NAME_LENGTH {
+        // Drop whole trailing words until it fits
+        while candidate.len() > MAX_NAME_LENGTH {
+            match candidate.rfind('_') {
+                Some(index) => candidate.truncate(index),
+                None => candidate.truncate(MAX_NAME_LENGTH),
+            }
+        }
+    }
+
+    let is_valid = |name: &str| {
+        let names = ProjectNames::from_input(name);
+        names.is_some_and(|names| {
+            !find_problems(&names, dependencies)
+                .iter()
+                .any(NameProblem::is_error)
+        })
+    };
+
+    if !is_valid(&candidate) {
+        candidate = format!("{}_game", candidate);
+    }
+
+    candidate
+}
+
+/// `2d_platformer` becomes `platformer_2d`, `3d` becomes `game_3d`
+fn move_leading_digits(input: &str) -> String {
+    let separator = if input.contains('-') { '-' } else { '_' };
+    let mut words: Vec<&str> = input.split(separator).filter(|w| !w.is_empty()).collect();
+
+    if words.len() > 1 {
+        let first = words.remove(0);
+        words.push(first);
+        words.join("_").to_ascii_lowercase()
+    } else {
+        format!("game_{}", input.to_ascii_lowercase())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors(input: &str) -> Vec<NameProblem> {
+        check_project_name(input, &["godot", "bevy_ecs"])
+            .errors()
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn accepts_regular_names() {
+        assert!(errors("my_game").is_empty());
+        assert!(errors("MyGame2").is_empty());
+        assert!(errors("space-invaders").is_empty());
+    }
+
+    #[test]
+    fn rejects_rust_keywords() {
+        assert_eq!(
+            errors("type"),
+            vec![NameProblem::RustKeyword("type".to_string())]
+        );
+        assert_eq!(
+            errors("Self"),
+            vec![NameProblem::RustKeyword("self".to_string())]
+        );
+    }
+
+    #[test]
+    fn rejects_reserved_and_dependency_names() {
+        assert!(errors("test").contains(&NameProblem::ReservedCrateName("test".to_string())));
+        assert!(errors("godot").contains(&NameProblem::ReservedCrateName("godot".to_string())));
+        assert_eq!(
+            errors("bevy-ecs"),
+            vec![NameProblem::DependencyName("bevy_ecs".to_string())]
+        );
+    }
+
+    #[test]
+    fn rejects_leading_digits_with_suggestion() {
+        let report = check_project_name("2d_platformer", &[]);
+        assert_eq!(report.problems, vec![NameProblem::LeadingDigit]);
+        assert_eq!(report.suggestion.as_deref(), Some("platformer_2d"));
+
+        let report = check_project_name("3d", &[]);
+        assert_eq!(report.suggestion.as_deref(), Some("game_3d"));
+    }
+
+    #[test]
+    fn rejects_names_that_are_too_long() {
+        let name = ["word"; 20].join("_");
+        let report = check_project_name(&name, &[]);
+
+        assert_eq!(report.problems, vec![NameProblem::TooLong(name.len())]);
+        let suggestion = report.suggestion.unwrap();
+        assert!(suggestion.len() <= MAX_NAME_LENGTH);
+        assert!(check_project_name(&suggestion, &[]).problems.is_empty());
+    }
+
+    #[test]
+    fn warns_about_godot_types() {
+        let report = check_project_name("node", &[]);
+        assert!(!report.has_errors());
+        assert_eq!(
+            report.warnings().cloned().collect::<Vec<_>>(),
+            vec![NameProblem::ShadowsGodotType("Node".to_string())]
+        );
+
+        assert!(!check_project_name("node_2d", &[]).problems.is_empty());
+    }
+
+    #[test]
+    fn suggestions_have_no_errors() {
+        for name in ["type", "self", "std", "godot"] {
+            let report = check_project_name(name, &["godot"]);
+            let suggestion = report.suggestion.unwrap();
+            assert!(
+                !check_project_name(&suggestion, &["godot"]).has_errors(),
+                "{}",
+                suggestion
+            );
+        }
+    }
+
+    #[test]
+    fn checks_class_names_and_modules() {
+        let names = check_class_name("player_ship").unwrap();
+        assert_eq!(names.pascal, "PlayerShip");
+        assert_eq!(names.snake, "player_ship");
+        assert!(check_class_name("Node2D").is_err());
+        assert!(check_class_name("2d_player").is_err());
+
+        assert_eq!(
+            check_module_path("actors::player").unwrap(),
+            ["actors", "player"]
+        );
+        assert_eq!(check_module_path("ui/hud").unwrap(), ["ui", "hud"]);
+        assert!(check_module_path("actors::type").is_err());
+        assert!(check_module_path("Actors").is_err());
+        assert!(check_module_path("actors::").is_err());
+    }
+}
@@ -19,12 +19,17 @@ pub fn detect_casing(input: &str) -> Casing {
         .chars()
         .any(|c| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'));
 
-    if (has_underscore && has_hyphen) || has_other_chars {
+    // Digits are allowed anywhere but at the start
+    let starts_with_digit = input.starts_with(|c: char| c.is_ascii_digit());
+
+    if (has_underscore && has_hyphen) || has_other_chars || starts_with_digit {
         return Casing::Undefined;
     }
 
+    let is_lower_or_digit = |c: char| c.is_ascii_lowercase() || c.is_ascii_digit();
+
     if has_underscore && !has_hyphen {
-        let all_chars_valid_for_snake = input.chars().all(|c| c.is_ascii_lowercase() || c == '_');
+        let all_chars_valid_for_snake = input.chars().all(|c| is_lower_or_digit(c) || c == '_');
         if all_chars_valid_for_snake {
             return Casing::SnakeCase;
         } else {
@@ -33,7 +38,7 @@ pub fn detect_casing(input: &str) -> Casing {
     }
 
     if has_hyphen && !has_underscore {
-        let all_chars_valid_for_kebab = input.chars().all(|c| c.is_ascii_lowercase() || c == '-');
+        let all_chars_valid_for_kebab = input.chars().all(|c| is_lower_or_digit(c) || c == '-');
         if all_chars_valid_for_kebab {
             return Casing::KebabCase;
         } else {
@@ -49,13 +54,11 @@ pub fn detect_casing(input: &str) -> Casing {
         if has_lowercase_internal || !input.chars().skip(1).any(|c| c.is_ascii_uppercase()) {
             return Casing::PascalCase;
         }
-    } else if first_char.is_ascii_lowercase() {
-        if has_uppercase_internal {
-            return Casing::CamelCase;
-        }
+    } else if first_char.is_ascii_lowercase() && has_uppercase_internal {
+        return Casing::CamelCase;
     }
 
-    if input.chars().all(|c| c.is_ascii_lowercase()) {
+    if input.chars().all(is_lower_or_digit) {
         return Casing::SnakeCase;
     }
 
@@ -104,14 +107,14 @@ fn get_words_from_name(name: &str, casing: Casing) -> Vec<String> {
                 let prev_char = chars[i - 1];
                 let current_char = chars[i];
 
-                if prev_char.is_ascii_lowercase() && current_char.is_ascii_uppercase() {
-                    words.push(chars[current_word_start_idx..i].iter().collect());
-                    current_word_start_idx = i;
-                } else if prev_char.is_ascii_uppercase()
+                let lower_to_upper =
+                    prev_char.is_ascii_lowercase() && current_char.is_ascii_uppercase();
+                let acronym_end = prev_char.is_ascii_uppercase()
                     && current_char.is_ascii_uppercase()
                     && i + 1 < chars.len()
-                    && chars[i + 1].is_ascii_lowercase()
-                {
+                    && chars[i + 1].is_ascii_lowercase();
+
+                if lower_to_upper || acronym_end {
                     words.push(chars[current_word_start_idx..i].iter().collect());
                     current_word_start_idx = i;
                 }
@@ -150,6 +153,48 @@ pub fn split_into_kebab_case(name: &str, detected_casing: Casing) -> String {
         .join("-")
 }
 
+pub fn split_into_snake_case(name: &str, detected_casing: Casing) -> String {
+    if detected_casing == Casing::Undefined {
+        return name.to_owned();
+    }
+
+    let words = get_words_from_name(name, detected_casing);
+    words
+        .into_iter()
+        .map(|word| to_all_lowercase(&word))
+        .collect::<Vec<String>>()
+        .join("_")
+}
+
+/// Every form of the project name used by the generated project
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ProjectNames {
+    /// Crate name (e.g., "my_game")
+    pub snake: String,
+    /// Project directory and Godot project name (e.g., "my-game")
+    pub kebab: String,
+    /// ExtensionLibrary struct name (e.g., "MyGame")
+    pub pascal: String,
+}
+
+impl ProjectNames {
+    /// Derives all name forms from a name in any supported casing
+    ///
+    /// Returns `None` when the casing can't be detected.
+    pub fn from_input(name: &str) -> Option<Self> {
+        let casing = detect_casing(name);
+        if casing == Casing::Undefined {
+            return None;
+        }
+
+        Some(Self {
+            snake: split_into_snake_case(name, casing),
+            kebab: split_into_kebab_case(name, casing),
+            pascal: split_into_pascal_case(&name.to_string(), casing),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -216,6 +261,15 @@ mod tests {
         assert_eq!(detect_casing("1_test"), Casing::Undefined);
     }
 
+    #[test]
+    fn test_detect_casing_with_digits() {
+        assert_eq!(detect_casing("game2"), Casing::SnakeCase);
+        assert_eq!(detect_casing("my_game_2d"), Casing::SnakeCase);
+        assert_eq!(detect_casing("my-game-2d"), Casing::KebabCase);
+        assert_eq!(detect_casing("MyGame2"), Casing::PascalCase);
+        assert_eq!(detect_casing("myGame2"), Casing::CamelCase);
+    }
+
     #[test]
     fn test_detect_casing_undefined_snake_with_uppercase() {
         assert_eq!(detect_casing("My_project"), Casing::Undefined);
@@ -301,4 +355,45 @@ mod tests {
             "AnotherGameThing"
         );
     }
+
+    #[test]
+    fn convert_to_snake() {
+        assert_eq!(
+            split_into_snake_case("MyPascalCaseName", Casing::PascalCase),
+            "my_pascal_case_name"
+        );
+        assert_eq!(
+            split_into_snake_case("myCamelCaseName", Casing::CamelCase),
+            "my_camel_case_name"
+        );
+        assert_eq!(
+            split_into_snake_case("my-kebab-case-name", Casing::KebabCase),
+            "my_kebab_case_name"
+        );
+        assert_eq!(
+            split_into_snake_case("HTTPRequestResponse", Casing::PascalCase),
+            "http_request_response"
+        );
+    }
+
+    #[test]
+    fn project_names_from_any_casing() {
+        let expected = ProjectNames {
+            snake: "my_game".to_string(),
+            kebab: "my-game".to_string(),
+            pascal: "MyGame".to_string(),
+        };
+
+        for input in ["my_game", "my-game", "MyGame", "myGame"] {
+            assert_eq!(
+                ProjectNames::from_input(input),
+                Some(expected.clone()),
+                "{}",
+                input
+            );
+        }
+
+        assert_eq!(ProjectNames::from_input("my game"), None);
+        assert_eq!(ProjectNames::from_input("MY_GAME"), None);
+    }
 }
@@ -0,0 +1,39 @@
+use std::env;
+use std::path::PathBuf;
+
+/// Returns godust's configuration directory
+///
+/// Follows the platform convention: `%APPDATA%\godust` on Windows,
+/// `$XDG_CONFIG_HOME/godust` or `~/.config/godust` everywhere else.
+pub fn config_dir() -> Option<PathBuf> {
+    if cfg!(windows) {
+        return env::var_os("APPDATA").map(|dir| PathBuf::from(dir).join("godust"));
+    }
+
+    env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| dir.join("godust"))
+}
+
+/// Returns the directory holding user templates (`<config dir>/templates`)
+pub fn user_templates_dir() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("templates"))
+}
+
+/// Returns godust's cache directory
+///
+/// `%LOCALAPPDATA%\godust` on Windows, `$XDG_CACHE_HOME/godust` or
+/// `~/.cache/godust` everywhere else.
+pub fn cache_dir() -> Option<PathBuf> {
+    if cfg!(windows) {
+        return env::var_os("LOCALAPPDATA").map(|dir| PathBuf::from(dir).join("godust"));
+    }
+
+    env::var_os("XDG_CACHE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .map(|dir| dir.join("godust"))
+}
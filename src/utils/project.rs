@@ -1,56 +1,276 @@
-use std::{
-    fs::{self},
-    io::{self},
-};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
 
 use crate::utils;
+use crate::utils::layout::{GAME_LOGIC_CRATE, ProjectLayout};
 
+/// Where the Godot project and the GDExtension crate go inside the project root
+///
+/// Both are patterns in which `{name}` is replaced by the snake_case project
+/// name and `{kebab_name}` by its kebab-case form. An empty pattern or `.`
+/// is the project root itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Project {
     core_project: String,
     engine_project: String,
 }
 
-const DEFAULT_CORE_TEMPLATE: &str = "{}/{}_core";
-const DEFAULT_ENGINE_TEMPLATE: &str = "{}/{}";
+pub const DEFAULT_CORE_DIR: &str = "{name}_core";
+pub const DEFAULT_ENGINE_DIR: &str = "{kebab_name}";
 
 impl Default for Project {
     fn default() -> Self {
         Self {
-            core_project: DEFAULT_CORE_TEMPLATE.to_string(),
-            engine_project: DEFAULT_ENGINE_TEMPLATE.to_string(),
+            core_project: DEFAULT_CORE_DIR.to_string(),
+            engine_project: DEFAULT_ENGINE_DIR.to_string(),
         }
     }
 }
 
 impl Project {
-    pub fn generate_structure(&self, name: &str) -> io::Result<()> {
+    /// Uses the given patterns, or the defaults for those left out
+    pub fn new(engine_project: Option<&str>, core_project: Option<&str>) -> Self {
+        let default = Self::default();
+        Self {
+            core_project: core_project.map_or(default.core_project, str::to_string),
+            engine_project: engine_project.map_or(default.engine_project, str::to_string),
+        }
+    }
+
+    /// Fills in the patterns for the project `name`
+    ///
+    /// Fails when a path leaves the project root, when the Godot project and
+    /// the crate share a directory, or when a workspace would put the crate
+    /// at the root, where the workspace manifest goes.
+    pub fn paths(&self, name: &str, layout: ProjectLayout) -> Result<ProjectPaths, String> {
         let kebab_name =
             utils::naming::split_into_kebab_case(name, utils::naming::Casing::SnakeCase);
+        let fill = |pattern: &str| {
+            normalize(
+                &pattern
+                    .replace("{name}", name)
+                    .replace("{kebab_name}", &kebab_name),
+            )
+        };
+
+        let paths = ProjectPaths {
+            layout,
+            godot_dir: fill(&self.engine_project)?,
+            core_dir: fill(&self.core_project)?,
+        };
+
+        if paths.godot_dir == paths.core_dir {
+            return Err(format!(
+                "The Godot project and the core crate can't share the directory '{}'",
+                display_dir(&paths.godot_dir)
+            ));
+        }
+        if layout == ProjectLayout::Workspace && paths.core_dir.is_empty() {
+            return Err(
+                "The core crate can't be at the root of a workspace, give it a directory"
+                    .to_string(),
+            );
+        }
 
-        let core_path = self
-            .core_project
-            .replacen("{}", &kebab_name, 1)
-            .replacen("{}", name, 1);
+        Ok(paths)
+    }
+}
 
-        let engine_path =
-            self.engine_project
-                .replacen("{}", &kebab_name, 1)
-                .replacen("{}", &kebab_name, 1);
+/// Resolved layout of a project, relative to its root
+///
+/// Directories are `/`-separated, as written to `Cargo.toml` and Godot
+/// files; an empty string is the project root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectPaths {
+    pub layout: ProjectLayout,
+    /// The Godot project (`res://`)
+    pub godot_dir: String,
+    /// The GDExtension crate
+    pub core_dir: String,
+}
 
-        fs::create_dir(&kebab_name)?;
-        fs::create_dir(&engine_path)?;
-        fs::create_dir(&core_path)?;
-        fs::create_dir(format!("{}/src", &core_path))?;
+impl ProjectPaths {
+    /// Cargo's build output directory
+    pub fn target_dir(&self) -> String {
+        match self.layout {
+            ProjectLayout::Standalone => join(&self.core_dir, "target"),
+            ProjectLayout::Workspace => "target".to_string(),
+        }
+    }
 
-        Ok(())
+    /// Directory `cargo build` runs in
+    pub fn build_dir(&self) -> PathBuf {
+        match self.layout {
+            ProjectLayout::Standalone => PathBuf::from(&self.core_dir),
+            ProjectLayout::Workspace => PathBuf::new(),
+        }
+    }
+
+    /// Directory holding the workspace crates, next to the core crate
+    pub fn crates_dir(&self) -> String {
+        self.core_dir
+            .rsplit_once('/')
+            .map_or(String::new(), |(parent, _)| parent.to_string())
+    }
+
+    /// Directories listed in the workspace `members`
+    pub fn workspace_members(&self, tools: &[String]) -> Vec<String> {
+        let crates_dir = self.crates_dir();
+        let tools_dir = join(&crates_dir, "tools");
+
+        [self.core_dir.clone(), join(&crates_dir, GAME_LOGIC_CRATE)]
+            .into_iter()
+            .chain(tools.iter().map(|tool| join(&tools_dir, tool)))
+            .collect()
     }
 
-    pub fn format_rendering_method(identifier: &str) -> String {
-        match identifier {
-            "forward_plus" => "Forward Plus".to_string(),
-            "gl_compatibility" => "GL Compatibility".to_string(),
-            "mobile" => "Mobile".to_string(),
-            _ => "Forward Plus".to_string(),
+    /// `res://` path of Cargo's target directory, for `.gdextension` files
+    pub fn library_dir(&self) -> String {
+        format!(
+            "res://{}",
+            relative_path(&self.godot_dir, &self.target_dir())
+        )
+    }
+
+    /// Rust directories inside the Godot project, which Godot shouldn't import
+    pub fn ignored_by_godot(&self, tools: &[String]) -> Vec<String> {
+        let mut dirs = vec![self.core_dir.clone(), self.target_dir()];
+        if self.layout == ProjectLayout::Workspace {
+            dirs.extend(self.workspace_members(tools).into_iter().skip(1));
         }
+
+        dirs.into_iter()
+            .filter(|dir| dir != &self.godot_dir && is_inside(dir, &self.godot_dir))
+            .collect()
+    }
+
+    /// Whether `path`, relative to the project root, belongs to the Godot
+    /// project rather than to one of the Rust directories inside it
+    pub fn is_godot_file(&self, path: &Path, tools: &[String]) -> bool {
+        path.starts_with(&self.godot_dir)
+            && !self
+                .ignored_by_godot(tools)
+                .iter()
+                .any(|dir| path.starts_with(dir))
+    }
+
+    /// Creates the directories the templates are written to inside `root`
+    pub fn generate_structure(&self, root: &Path) -> io::Result<()> {
+        fs::create_dir_all(root.join(&self.godot_dir))?;
+        fs::create_dir_all(root.join(&self.core_dir).join("src"))?;
+
+        Ok(())
+    }
+}
+
+/// Shows a directory relative to the project root, `.` for the root itself
+pub fn display_dir(dir: &str) -> &str {
+    if dir.is_empty() { "." } else { dir }
+}
+
+/// Turns a user-provided directory into `/`-separated components
+fn normalize(dir: &str) -> Result<String, String> {
+    if dir.starts_with(['/', '\\']) || dir.contains(':') {
+        return Err(format!(
+            "'{}' must be relative to the project root, not absolute",
+            dir
+        ));
+    }
+
+    let components: Vec<&str> = dir
+        .split(['/', '\\'])
+        .filter(|c| !c.is_empty() && *c != ".")
+        .collect();
+
+    if components.contains(&"..") {
+        return Err(format!("'{}' must stay inside the project root", dir));
+    }
+
+    Ok(components.join("/"))
+}
+
+fn join(dir: &str, name: &str) -> String {
+    if dir.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", dir, name)
+    }
+}
+
+fn components(dir: &str) -> Vec<&str> {
+    dir.split('/').filter(|c| !c.is_empty()).collect()
+}
+
+fn is_inside(dir: &str, parent: &str) -> bool {
+    components(dir).starts_with(&components(parent))
+}
+
+/// Path from the directory `from` to `to`, both relative to the same root
+fn relative_path(from: &str, to: &str) -> String {
+    let from = components(from);
+    let to = components(to);
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+
+    let mut path = vec![".."; from.len() - common];
+    path.extend(&to[common..]);
+    path.join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(godot: &str, core: &str, layout: ProjectLayout) -> Result<ProjectPaths, String> {
+        Project::new(Some(godot), Some(core)).paths("my_game", layout)
+    }
+
+    #[test]
+    fn default_layout_matches_the_historical_one() {
+        let paths = Project::default()
+            .paths("my_game", ProjectLayout::Standalone)
+            .unwrap();
+
+        assert_eq!(paths.godot_dir, "my-game");
+        assert_eq!(paths.core_dir, "my_game_core");
+        assert_eq!(paths.target_dir(), "my_game_core/target");
+        assert_eq!(paths.library_dir(), "res://../my_game_core/target");
+        assert!(paths.ignored_by_godot(&[]).is_empty());
+    }
+
+    #[test]
+    fn library_dir_follows_the_real_location() {
+        let standalone = ProjectLayout::Standalone;
+        let workspace = ProjectLayout::Workspace;
+
+        let split = paths("godot", "rust", standalone).unwrap();
+        assert_eq!(split.library_dir(), "res://../rust/target");
+
+        let godot_at_root = paths(".", "rust/{name}", standalone).unwrap();
+        assert_eq!(godot_at_root.library_dir(), "res://rust/my_game/target");
+        assert_eq!(
+            godot_at_root.ignored_by_godot(&[]),
+            ["rust/my_game", "rust/my_game/target"]
+        );
+        assert!(godot_at_root.is_godot_file(Path::new("main.tscn"), &[]));
+        assert!(!godot_at_root.is_godot_file(Path::new("rust/my_game/src/lib.rs"), &[]));
+
+        let nested = paths("game/{kebab_name}", "rust/core", workspace).unwrap();
+        assert_eq!(nested.library_dir(), "res://../../target");
+        assert_eq!(
+            nested.workspace_members(&["editor".to_string()]),
+            ["rust/core", "rust/game_logic", "rust/tools/editor"]
+        );
+    }
+
+    #[test]
+    fn rejects_paths_outside_the_root_or_shared() {
+        let standalone = ProjectLayout::Standalone;
+
+        assert!(paths("../godot", "rust", standalone).is_err());
+        assert!(paths("/tmp/godot", "rust", standalone).is_err());
+        assert!(paths("game", "./game/", standalone).is_err());
+        assert!(paths("godot", ".", standalone).is_ok());
+        assert!(paths("godot", ".", ProjectLayout::Workspace).is_err());
     }
 }
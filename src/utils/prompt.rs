@@ -0,0 +1,165 @@
+use std::io::{self, BufRead, IsTerminal, Write};
+
+/// Returns true when the user can answer prompts
+pub fn is_interactive() -> bool {
+    io::stdin().is_terminal()
+}
+
+/// Asks for a free-form value on the terminal
+///
+/// See [`input_from`].
+pub fn input(
+    question: &str,
+    default: Option<&str>,
+    validate: impl Fn(&str) -> Result<(), String>,
+) -> io::Result<String> {
+    input_from(
+        &mut io::stdin().lock(),
+        &mut io::stdout(),
+        question,
+        default,
+        validate,
+    )
+}
+
+/// Asks the user to pick one of `choices` on the terminal
+///
+/// See [`select_from`].
+pub fn select(question: &str, choices: &[String], default: usize) -> io::Result<usize> {
+    select_from(
+        &mut io::stdin().lock(),
+        &mut io::stdout(),
+        question,
+        choices,
+        default,
+    )
+}
+
+/// Asks for a free-form value until `validate` accepts it
+///
+/// An empty answer selects `default` when there is one.
+pub fn input_from(
+    reader: &mut impl BufRead,
+    writer: &mut impl Write,
+    question: &str,
+    default: Option<&str>,
+    validate: impl Fn(&str) -> Result<(), String>,
+) -> io::Result<String> {
+    loop {
+        match default {
+            Some(default) => write!(writer, "{} [{}]: ", question, default)?,
+            None => write!(writer, "{}: ", question)?,
+        }
+        writer.flush()?;
+
+        let answer = read_answer(reader)?;
+        let answer = match (answer.is_empty(), default) {
+            (true, Some(default)) => default.to_string(),
+            _ => answer,
+        };
+
+        match validate(&answer) {
+            Ok(()) => return Ok(answer),
+            Err(message) => writeln!(writer, "   {}", message)?,
+        }
+    }
+}
+
+/// Asks the user to pick one of `choices` by number
+///
+/// Returns the index of the selected choice; an empty answer selects `default`.
+pub fn select_from(
+    reader: &mut impl BufRead,
+    writer: &mut impl Write,
+    question: &str,
+    choices: &[String],
+    default: usize,
+) -> io::Result<usize> {
+    writeln!(writer, "{}", question)?;
+    for (i, choice) in choices.iter().enumerate() {
+        writeln!(writer, "   {}) {}", i + 1, choice)?;
+    }
+
+    let default_answer = (default + 1).to_string();
+    let answer = input_from(
+        reader,
+        writer,
+        "Choose",
+        Some(&default_answer),
+        |answer| match answer.parse::<usize>() {
+            Ok(n) if (1..=choices.len()).contains(&n) => Ok(()),
+            _ => Err(format!("Please enter a number from 1 to {}", choices.len())),
+        },
+    )?;
+
+    Ok(answer.parse::<usize>().unwrap_or(default + 1) - 1)
+}
+
+fn read_answer(reader: &mut impl BufRead) -> io::Result<String> {
+    let mut line = String::new();
+    if reader.read_line(&mut line)? == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "input closed before an answer was given",
+        ));
+    }
+    Ok(line.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn input_reprompts_until_valid() {
+        let mut reader = Cursor::new("Bad Name\nmy_game\n");
+        let mut output = Vec::new();
+
+        let answer = input_from(&mut reader, &mut output, "Name", None, |a| {
+            if a.contains(' ') {
+                Err("no spaces".to_string())
+            } else {
+                Ok(())
+            }
+        })
+        .unwrap();
+
+        assert_eq!(answer, "my_game");
+        assert!(String::from_utf8(output).unwrap().contains("no spaces"));
+    }
+
+    #[test]
+    fn input_uses_default_on_empty_answer() {
+        let mut reader = Cursor::new("\n");
+        let answer = input_from(&mut reader, &mut Vec::new(), "Engine", Some("4.5"), |_| {
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(answer, "4.5");
+    }
+
+    #[test]
+    fn select_returns_chosen_index() {
+        let choices = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+
+        let mut reader = Cursor::new("7\n3\n");
+        assert_eq!(
+            select_from(&mut reader, &mut Vec::new(), "Pick", &choices, 0).unwrap(),
+            2
+        );
+
+        let mut reader = Cursor::new("\n");
+        assert_eq!(
+            select_from(&mut reader, &mut Vec::new(), "Pick", &choices, 1).unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn closed_input_is_an_error() {
+        let mut reader = Cursor::new("");
+        let err = input_from(&mut reader, &mut Vec::new(), "Name", None, |_| Ok(())).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+}
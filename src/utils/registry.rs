@@ -0,0 +1,386 @@
+pub mod cargo_config;
+
+use semver::Version;
+use serde::Deserialize;
+use std::error::Error;
+use std::fmt;
+use std::time::Duration;
+
+const USER_AGENT: &str = "GodustCLI/0.1 (https://github.com/viniciusmorgado/godust)";
+
+/// Longest a single registry request may take, connection included
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Attempts per request when the registry times out or fails on its side
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry, doubled for each following one
+const RETRY_DELAY: Duration = Duration::from_millis(250);
+
+/// Error type of registry requests, sendable so lookups can run on other tasks
+pub type RegistryError = Box<dyn Error + Send + Sync>;
+
+/// Base URL of the crates.io web API
+pub const CRATES_IO_API: &str = "https://crates.io";
+
+/// Where crate versions are looked up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Registry {
+    /// A crates.io-compatible web API (`<base>/api/v1/crates/<name>`)
+    WebApi { base_url: String },
+    /// A sparse index, read one crate file (NDJSON) at a time; `config.json`
+    /// only matters for downloading and publishing, so it isn't fetched
+    Sparse { index_url: String },
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Registry::WebApi {
+            base_url: CRATES_IO_API.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Registry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Registry::WebApi { base_url } => write!(f, "{}", base_url),
+            Registry::Sparse { index_url } => write!(f, "sparse+{}", index_url),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct CrateApiResponse {
+    #[serde(rename = "crate")]
+    crate_data: CrateInfo,
+    #[serde(default)]
+    versions: Vec<ApiVersion>,
+}
+
+#[derive(Deserialize)]
+struct CrateInfo {
+    max_stable_version: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ApiVersion {
+    num: String,
+    #[serde(default)]
+    yanked: bool,
+}
+
+/// One line of a sparse index crate file
+#[derive(Deserialize)]
+struct IndexEntry {
+    vers: String,
+    #[serde(default)]
+    yanked: bool,
+}
+
+impl Registry {
+    /// Parses a registry URL
+    ///
+    /// `sparse+<url>` selects a sparse index, any other URL a web API.
+    pub fn parse(url: &str) -> Result<Self, String> {
+        let (sparse, url) = match url.strip_prefix("sparse+") {
+            Some(url) => (true, url),
+            None => (false, url),
+        };
+
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(format!("'{}' is not an http(s) registry URL", url));
+        }
+
+        Ok(if sparse {
+            Registry::Sparse {
+                index_url: format!("{}/", url.trim_end_matches('/')),
+            }
+        } else {
+            Registry::WebApi {
+                base_url: url.trim_end_matches('/').to_string(),
+            }
+        })
+    }
+
+    /// Picks the registry to use
+    ///
+    /// In order: `cli` (`--registry`), `config` (godust's config file), the
+    /// crates.io source replacement from Cargo's config, then crates.io.
+    pub fn select(cli: Option<&str>, config: Option<&str>) -> Result<Self, String> {
+        if let Some(url) = cli.or(config) {
+            return Self::parse(url);
+        }
+
+        Ok(cargo_config::crates_io_replacement().unwrap_or_default())
+    }
+
+    /// Fetches the published versions of `crate_name` in a single request
+    pub async fn published(
+        &self,
+        client: &reqwest::Client,
+        crate_name: &str,
+    ) -> Result<PublishedVersions, RegistryError> {
+        let (max_stable, entries): (Option<String>, Vec<(String, bool)>) = match self {
+            Registry::WebApi { base_url } => {
+                let response = fetch_api(client, base_url, crate_name).await?;
+                let entries = response
+                    .versions
+                    .into_iter()
+                    .map(|v| (v.num, v.yanked))
+                    .collect();
+                (response.crate_data.max_stable_version, entries)
+            }
+            Registry::Sparse { index_url } => {
+                let url = format!("{}{}", index_url, sparse_index_path(crate_name));
+                let entries = parse_index_file(&get(client, &url).await?.text().await?)?
+                    .into_iter()
+                    .map(|entry| (entry.vers, entry.yanked))
+                    .collect();
+                (None, entries)
+            }
+        };
+
+        let versions: Vec<Version> = entries
+            .iter()
+            .filter(|(_, yanked)| !yanked)
+            .filter_map(|(version, _)| Version::parse(version).ok())
+            .collect();
+        let latest_stable = max_stable.or_else(|| {
+            versions
+                .iter()
+                .filter(|v| v.pre.is_empty())
+                .max()
+                .map(|v| v.to_string())
+        });
+
+        Ok(PublishedVersions {
+            latest_stable,
+            versions,
+        })
+    }
+}
+
+/// What a registry knows about a crate
+#[derive(Debug, Clone, PartialEq)]
+pub struct PublishedVersions {
+    /// Newest stable, non-yanked version; `None` when only pre-releases exist
+    pub latest_stable: Option<String>,
+    /// Every non-yanked version
+    pub versions: Vec<Version>,
+}
+
+/// Builds the HTTP client shared by every registry request of a run
+pub fn http_client() -> reqwest::Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .user_agent(USER_AGENT)
+        .timeout(REQUEST_TIMEOUT)
+        .connect_timeout(CONNECT_TIMEOUT)
+        .build()
+}
+
+async fn fetch_api(
+    client: &reqwest::Client,
+    base_url: &str,
+    crate_name: &str,
+) -> Result<CrateApiResponse, RegistryError> {
+    let url = format!("{}/api/v1/crates/{}", base_url, crate_name);
+
+    Ok(get(client, &url).await?.json().await?)
+}
+
+/// Sends a GET request, retrying timeouts, connection failures and 5xx/429 responses
+async fn get(client: &reqwest::Client, url: &str) -> reqwest::Result<reqwest::Response> {
+    let mut attempt = 1;
+    loop {
+        let result = client
+            .get(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+
+        match result {
+            Err(e) if attempt < MAX_ATTEMPTS && is_transient(&e) => {
+                tokio::time::sleep(RETRY_DELAY * 2u32.pow(attempt - 1)).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Whether a failed request may succeed when sent again
+fn is_transient(error: &reqwest::Error) -> bool {
+    match error.status() {
+        Some(status) => {
+            status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+        }
+        None => error.is_timeout() || error.is_connect() || error.is_request(),
+    }
+}
+
+/// Path of a crate's file inside a sparse index, as laid out by Cargo
+fn sparse_index_path(crate_name: &str) -> String {
+    let name = crate_name.to_ascii_lowercase();
+    match name.len() {
+        1 => format!("1/{}", name),
+        2 => format!("2/{}", name),
+        3 => format!("3/{}/{}", &name[..1], name),
+        _ => format!("{}/{}/{}", &name[..2], &name[2..4], name),
+    }
+}
+
+/// Parses a sparse index crate file: one JSON object per line
+fn parse_index_file(contents: &str) -> Result<Vec<IndexEntry>, RegistryError> {
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| Ok(serde_json::from_str(line)?))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    #[test]
+    fn parses_registry_urls() {
+        assert_eq!(
+            Registry::parse("sparse+https://index.crates.io").unwrap(),
+            Registry::Sparse {
+                index_url: "https://index.crates.io/".to_string()
+            }
+        );
+        assert_eq!(
+            Registry::parse("http://mirror.local:8080/").unwrap(),
+            Registry::WebApi {
+                base_url: "http://mirror.local:8080".to_string()
+            }
+        );
+        assert!(Registry::parse("git://example.com/index").is_err());
+    }
+
+    #[test]
+    fn lays_out_index_paths_like_cargo() {
+        assert_eq!(sparse_index_path("a"), "1/a");
+        assert_eq!(sparse_index_path("gd"), "2/gd");
+        assert_eq!(sparse_index_path("ecs"), "3/e/ecs");
+        assert_eq!(sparse_index_path("Godot"), "go/do/godot");
+        assert_eq!(sparse_index_path("bevy_ecs"), "be/vy/bevy_ecs");
+    }
+
+    /// Serves HTTP on a local port and returns the base URL
+    ///
+    /// `respond` maps each request path to a status line and body.
+    fn serve_with(respond: impl Fn(&str) -> (&'static str, String) + Send + 'static) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut request_line = String::new();
+                BufReader::new(&stream)
+                    .read_line(&mut request_line)
+                    .unwrap();
+
+                let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+                let (status, body) = respond(path);
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+
+        format!("http://{}", address)
+    }
+
+    /// Serves `files` by path, and 404 for anything else
+    fn serve(files: &'static [(&'static str, &'static str)]) -> String {
+        serve_with(|path| match files.iter().find(|(file, _)| *file == path) {
+            Some((_, body)) => ("200 OK", body.to_string()),
+            None => ("404 Not Found", String::new()),
+        })
+    }
+
+    #[tokio::test]
+    async fn resolves_versions_from_a_sparse_index() {
+        let url = serve(&[(
+            "/index/go/do/godot",
+            concat!(
+                r#"{"name":"godot","vers":"0.3.0","yanked":false}"#,
+                "\n",
+                r#"{"name":"godot","vers":"0.4.1","yanked":false}"#,
+                "\n",
+                r#"{"name":"godot","vers":"0.4.2","yanked":true}"#,
+                "\n",
+                r#"{"name":"godot","vers":"0.5.0-rc.1","yanked":false}"#,
+                "\n",
+            ),
+        )]);
+        let registry = Registry::parse(&format!("sparse+{}/index", url)).unwrap();
+        let client = http_client().unwrap();
+
+        let godot = registry.published(&client, "godot").await.unwrap();
+        assert_eq!(godot.latest_stable.as_deref(), Some("0.4.1"));
+        assert_eq!(godot.versions.len(), 3);
+        assert!(registry.published(&client, "bevy_ecs").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn resolves_versions_from_a_web_api() {
+        let url = serve(&[(
+            "/api/v1/crates/godot",
+            r#"{"crate":{"max_stable_version":"0.4.1"},"versions":[{"num":"0.4.1","yanked":false},{"num":"0.4.0","yanked":true}]}"#,
+        )]);
+        let registry = Registry::parse(&url).unwrap();
+        let client = http_client().unwrap();
+
+        assert_eq!(
+            registry.published(&client, "godot").await.unwrap(),
+            PublishedVersions {
+                latest_stable: Some("0.4.1".to_string()),
+                versions: vec![Version::new(0, 4, 1)],
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn retries_server_errors_but_not_missing_crates() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        let url = serve_with(move |path| {
+            let attempt = counter.fetch_add(1, Ordering::SeqCst);
+            match path {
+                "/api/v1/crates/godot" if attempt == 0 => {
+                    ("503 Service Unavailable", String::new())
+                }
+                "/api/v1/crates/godot" => (
+                    "200 OK",
+                    r#"{"crate":{"max_stable_version":"0.4.1"}}"#.to_string(),
+                ),
+                _ => ("404 Not Found", String::new()),
+            }
+        });
+        let registry = Registry::parse(&url).unwrap();
+        let client = http_client().unwrap();
+
+        let godot = registry.published(&client, "godot").await.unwrap();
+        assert_eq!(godot.latest_stable.as_deref(), Some("0.4.1"));
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        assert!(registry.published(&client, "missing").await.is_err());
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+}
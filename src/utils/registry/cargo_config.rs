@@ -0,0 +1,175 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+use super::Registry;
+
+/// Git URL of the crates.io index, which is crates.io itself
+const CRATES_IO_GIT_INDEX: &str = "https://github.com/rust-lang/crates.io-index";
+
+/// Longest `replace-with` chain followed before giving up on a cycle
+const MAX_REPLACEMENTS: usize = 16;
+
+/// Returns the registry Cargo's config replaces crates.io with, if any
+///
+/// Reads the same files as Cargo: `.cargo/config.toml` (or `.cargo/config`)
+/// in the current directory and its parents, then in `$CARGO_HOME`. Only
+/// sparse registries can be queried; other replacements print a warning and
+/// are ignored.
+pub fn crates_io_replacement() -> Option<Registry> {
+    let tables: Vec<Table> = config_files()
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok()?.parse().ok())
+        .collect();
+
+    match replacement_in(&tables) {
+        Ok(registry) => registry,
+        Err(e) => {
+            eprintln!(
+                "⚠️  Warning: Ignoring Cargo's crates.io source replacement: {}",
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Cargo config files, highest priority first
+fn config_files() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = env::current_dir()
+        .map(|cwd| cwd.ancestors().map(|dir| dir.join(".cargo")).collect())
+        .unwrap_or_default();
+
+    let cargo_home = env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cargo")));
+    if let Some(cargo_home) = cargo_home
+        && !dirs.contains(&cargo_home)
+    {
+        dirs.push(cargo_home);
+    }
+
+    dirs.iter()
+        .filter_map(|dir| {
+            ["config.toml", "config"]
+                .iter()
+                .map(|file| dir.join(file))
+                .find(|path| path.is_file())
+        })
+        .collect()
+}
+
+/// Follows `[source.crates-io] replace-with` through `tables`
+///
+/// The first table defining a source or registry wins, as Cargo merges
+/// closer config files over farther ones.
+fn replacement_in(tables: &[Table]) -> Result<Option<Registry>, String> {
+    let lookup = |section: &str, name: &str| {
+        tables
+            .iter()
+            .find_map(|table| table.get(section)?.get(name)?.as_table())
+    };
+    let string =
+        |table: &Table, key: &str| table.get(key).and_then(Value::as_str).map(str::to_string);
+
+    let mut name = "crates-io".to_string();
+    for _ in 0..MAX_REPLACEMENTS {
+        let source = lookup("source", &name);
+
+        if let Some(next) = source.and_then(|s| string(s, "replace-with")) {
+            name = next;
+            continue;
+        }
+        if name == "crates-io" {
+            return Ok(None);
+        }
+
+        let url = match source {
+            Some(source) => string(source, "registry").ok_or_else(|| {
+                format!(
+                    "source '{}' is not a registry (local and directory sources can't be queried)",
+                    name
+                )
+            })?,
+            None => lookup("registries", &name)
+                .and_then(|registry| string(registry, "index"))
+                .ok_or_else(|| format!("source '{}' is not defined", name))?,
+        };
+
+        if url.trim_end_matches('/') == CRATES_IO_GIT_INDEX {
+            return Ok(None);
+        }
+        if !url.starts_with("sparse+") {
+            return Err(format!(
+                "'{}' is a git index, only sparse indexes can be queried",
+                url
+            ));
+        }
+        return Registry::parse(&url).map(Some);
+    }
+
+    Err("the replace-with chain is too long or has a cycle".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tables(configs: &[&str]) -> Vec<Table> {
+        configs.iter().map(|c| c.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn no_replacement_keeps_crates_io() {
+        assert_eq!(replacement_in(&[]), Ok(None));
+        assert_eq!(
+            replacement_in(&tables(&[
+                "[registries.other]\nindex = \"sparse+https://x/\"\n"
+            ])),
+            Ok(None)
+        );
+    }
+
+    #[test]
+    fn follows_source_replacement() {
+        let config = r#"
+[source.crates-io]
+replace-with = "mirror"
+
+[source.mirror]
+registry = "sparse+https://mirror.example.com/index/"
+"#;
+        assert_eq!(
+            replacement_in(&tables(&[config])),
+            Ok(Some(Registry::Sparse {
+                index_url: "https://mirror.example.com/index/".to_string()
+            }))
+        );
+    }
+
+    #[test]
+    fn replacement_can_name_a_registry() {
+        let project = "[source.crates-io]\nreplace-with = \"company\"\n";
+        let home = "[registries.company]\nindex = \"sparse+http://127.0.0.1:8080\"\n";
+
+        assert_eq!(
+            replacement_in(&tables(&[project, home])),
+            Ok(Some(Registry::Sparse {
+                index_url: "http://127.0.0.1:8080/".to_string()
+            }))
+        );
+    }
+
+    #[test]
+    fn unsupported_replacements_are_errors() {
+        let vendored = "[source.crates-io]\nreplace-with = \"vendored\"\n[source.vendored]\ndirectory = \"vendor\"\n";
+        let git = "[source.crates-io]\nreplace-with = \"git\"\n[source.git]\nregistry = \"https://example.com/index.git\"\n";
+        let cycle =
+            "[source.crates-io]\nreplace-with = \"a\"\n[source.a]\nreplace-with = \"crates-io\"\n";
+
+        assert!(replacement_in(&tables(&[vendored])).is_err());
+        assert!(replacement_in(&tables(&[git])).is_err());
+        assert!(replacement_in(&tables(&[cycle])).is_err());
+    }
+}
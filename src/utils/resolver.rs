@@ -0,0 +1,469 @@
+use semver::{Version, VersionReq};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::task::JoinSet;
+
+use crate::error::GodustError;
+use crate::utils::crates_version::{
+    KNOWN_GOOD_VERSIONS, ResolvedVersion, VersionPin, VersionSource, newest_matching,
+};
+use crate::utils::registry::{self, PublishedVersions, Registry, RegistryError};
+use crate::utils::version_cache::{CachedVersion, VersionCache};
+
+/// A dependency whose version has to be resolved
+#[derive(Debug, Clone)]
+pub struct DependencyRequest {
+    pub crate_name: String,
+    /// Version declared in the template manifest, used as the last resort
+    pub template_fallback: String,
+    pub pin: Option<VersionPin>,
+}
+
+/// Resolved versions in request order, with where each came from
+#[derive(Debug, Default)]
+pub struct ResolutionReport {
+    pub dependencies: Vec<(String, ResolvedVersion)>,
+}
+
+impl ResolutionReport {
+    pub fn get(&self, crate_name: &str) -> Option<&ResolvedVersion> {
+        self.dependencies
+            .iter()
+            .find(|(name, _)| name == crate_name)
+            .map(|(_, resolved)| resolved)
+    }
+
+    /// Number of dependencies resolved from `source`
+    pub fn count(&self, source: VersionSource) -> usize {
+        self.dependencies
+            .iter()
+            .filter(|(_, resolved)| resolved.source == source)
+            .count()
+    }
+
+    /// One line summary such as "2 dependencies: 1 from the registry, 1 pinned"
+    pub fn summary(&self) -> String {
+        let fallbacks =
+            self.count(VersionSource::KnownGood) + self.count(VersionSource::TemplateFallback);
+        let counts = [
+            (self.count(VersionSource::Network), "from the registry"),
+            (self.count(VersionSource::Cache), "cached"),
+            (self.count(VersionSource::Pinned), "pinned"),
+            (fallbacks, "fallback"),
+        ];
+
+        let parts: Vec<String> = counts
+            .iter()
+            .filter(|(count, _)| *count > 0)
+            .map(|(count, label)| format!("{} {}", count, label))
+            .collect();
+
+        let total = self.dependencies.len();
+        let noun = if total == 1 {
+            "dependency"
+        } else {
+            "dependencies"
+        };
+        if parts.is_empty() {
+            format!("{} {}", total, noun)
+        } else {
+            format!("{} {}: {}", total, noun, parts.join(", "))
+        }
+    }
+}
+
+/// Resolves dependency versions from one registry with one HTTP client
+///
+/// Every dependency is resolved concurrently. Requests time out and are
+/// retried by the [`registry`] functions; the version cache is shared by
+/// all lookups and written once they are done.
+pub struct VersionResolver {
+    registry: Registry,
+    client: reqwest::Client,
+    /// Never contact the registry
+    offline: bool,
+    cache: Mutex<VersionCache>,
+    /// Whether anything was fetched into the cache since it was loaded
+    cache_updated: AtomicBool,
+}
+
+impl VersionResolver {
+    pub fn new(
+        registry: Registry,
+        offline: bool,
+        cache: VersionCache,
+    ) -> Result<Arc<Self>, GodustError> {
+        let client = registry::http_client().map_err(|e| {
+            GodustError::Network(format!("Could not create the HTTP client: {}", e))
+        })?;
+
+        Ok(Arc::new(Self {
+            registry,
+            client,
+            offline,
+            cache: Mutex::new(cache),
+            cache_updated: AtomicBool::new(false),
+        }))
+    }
+
+    /// Resolves every request concurrently
+    ///
+    /// Fails with the first request, in order, that can't be resolved.
+    pub async fn resolve_all(
+        self: &Arc<Self>,
+        requests: Vec<DependencyRequest>,
+    ) -> Result<ResolutionReport, GodustError> {
+        let mut tasks = JoinSet::new();
+        for (index, request) in requests.into_iter().enumerate() {
+            let resolver = Arc::clone(self);
+            tasks.spawn(async move {
+                let resolved = resolver.resolve(&request).await;
+                (index, request.crate_name, resolved)
+            });
+        }
+
+        let mut results = Vec::new();
+        while let Some(joined) = tasks.join_next().await {
+            results.push(joined.map_err(|e| GodustError::Network(e.to_string()))?);
+        }
+        results.sort_by_key(|(index, ..)| *index);
+
+        if self.cache_updated.load(Ordering::Relaxed)
+            && let Err(e) = self.lock_cache().save()
+        {
+            eprintln!("⚠️  Warning: Could not write the version cache: {}", e);
+        }
+
+        let dependencies = results
+            .into_iter()
+            .map(|(_, crate_name, resolved)| Ok((crate_name, resolved?)))
+            .collect::<Result<_, String>>()
+            .map_err(GodustError::Network)?;
+
+        Ok(ResolutionReport { dependencies })
+    }
+
+    /// Resolves the version of one dependency
+    ///
+    /// An exact pin is used as is and a requirement is matched against the
+    /// published versions. Without a pin the latest stable version is used,
+    /// see [`Self::resolve_latest`].
+    pub async fn resolve(&self, request: &DependencyRequest) -> Result<ResolvedVersion, String> {
+        let crate_name = &request.crate_name;
+        let fallback = &request.template_fallback;
+
+        match &request.pin {
+            None => Ok(self.resolve_latest(crate_name, fallback).await),
+            Some(VersionPin::Exact(version)) => Ok(ResolvedVersion {
+                version: version.to_string(),
+                source: VersionSource::Pinned,
+            }),
+            Some(VersionPin::Requirement(req)) => {
+                self.resolve_requirement(crate_name, req, fallback).await
+            }
+        }
+    }
+
+    /// Resolves the latest stable version of `crate_name`
+    ///
+    /// Uses, in order: a cached version younger than
+    /// [`CACHE_TTL`](crate::utils::version_cache::CACHE_TTL), the registry,
+    /// a cached version of any age, [`KNOWN_GOOD_VERSIONS`] and finally
+    /// `template_fallback`. A warning is printed whenever the version isn't
+    /// the latest known one.
+    async fn resolve_latest(&self, crate_name: &str, template_fallback: &str) -> ResolvedVersion {
+        let cached = self.cached(crate_name);
+
+        if let Some(cached) = &cached
+            && (self.offline || cached.is_fresh())
+        {
+            return ResolvedVersion {
+                version: cached.version.clone(),
+                source: VersionSource::Cache,
+            };
+        }
+
+        if !self.offline {
+            let latest = self.fetch(crate_name).await.and_then(|published| {
+                published
+                    .latest_stable
+                    .ok_or_else(|| format!("'{}' has no stable version", crate_name).into())
+            });
+            match latest {
+                Ok(version) => {
+                    return ResolvedVersion {
+                        version,
+                        source: VersionSource::Network,
+                    };
+                }
+                Err(e) => {
+                    eprintln!(
+                        "⚠️  Warning: Could not fetch the latest '{}' version from {}: {}",
+                        crate_name, self.registry, e
+                    );
+                    if let Some(cached) = cached {
+                        eprintln!(
+                            "   Using '{}' {} cached {} hours ago.",
+                            crate_name,
+                            cached.version,
+                            cached.age().as_secs() / 3600
+                        );
+                        return ResolvedVersion {
+                            version: cached.version,
+                            source: VersionSource::Cache,
+                        };
+                    }
+                }
+            }
+        }
+
+        let resolved = match known_good_version(crate_name) {
+            Some(version) => ResolvedVersion {
+                version: version.to_string(),
+                source: VersionSource::KnownGood,
+            },
+            None => ResolvedVersion {
+                version: template_fallback.to_string(),
+                source: VersionSource::TemplateFallback,
+            },
+        };
+        warn_fallback(crate_name, &resolved);
+
+        resolved
+    }
+
+    /// Resolves the newest version of `crate_name` matching `req`
+    ///
+    /// Matches against the versions cached by an earlier run while they are
+    /// fresh, then against the registry. Without the registry only the
+    /// cached, known-good and template fallback versions are candidates; it
+    /// is an error when none of them matches.
+    async fn resolve_requirement(
+        &self,
+        crate_name: &str,
+        req: &VersionReq,
+        template_fallback: &str,
+    ) -> Result<ResolvedVersion, String> {
+        let cached = self
+            .cached(crate_name)
+            .and_then(|cached| Some((cached_matching(&cached, req)?, cached.is_fresh())));
+
+        if let Some((version, fresh)) = &cached
+            && (self.offline || *fresh)
+        {
+            return Ok(ResolvedVersion {
+                version: version.clone(),
+                source: VersionSource::Cache,
+            });
+        }
+
+        if !self.offline {
+            match self.fetch(crate_name).await {
+                Ok(published) => {
+                    let newest = newest_matching(&published.versions, req).ok_or_else(|| {
+                        format!("No published version of '{}' matches '{}'", crate_name, req)
+                    })?;
+                    return Ok(ResolvedVersion {
+                        version: newest.to_string(),
+                        source: VersionSource::Network,
+                    });
+                }
+                Err(e) => {
+                    eprintln!(
+                        "⚠️  Warning: Could not fetch the '{}' versions from {}: {}",
+                        crate_name, self.registry, e
+                    );
+                }
+            }
+        }
+
+        if let Some((version, _)) = cached {
+            return Ok(ResolvedVersion {
+                version,
+                source: VersionSource::Cache,
+            });
+        }
+
+        let known_good = KNOWN_GOOD_VERSIONS
+            .iter()
+            .filter(|(name, _)| *name == crate_name)
+            .map(|(_, version)| (version.to_string(), VersionSource::KnownGood));
+        let candidates = known_good.chain([(
+            template_fallback.to_string(),
+            VersionSource::TemplateFallback,
+        )]);
+
+        for (version, source) in candidates {
+            if Version::parse(&version).is_ok_and(|v| req.matches(&v)) {
+                let resolved = ResolvedVersion { version, source };
+                warn_fallback(crate_name, &resolved);
+                return Ok(resolved);
+            }
+        }
+
+        Err(format!(
+            "No version of '{}' matching '{}' is known without the registry; pin an exact version instead",
+            crate_name, req
+        ))
+    }
+
+    /// Fetches `crate_name` from the registry and caches what was found
+    async fn fetch(&self, crate_name: &str) -> Result<PublishedVersions, RegistryError> {
+        let published = self.registry.published(&self.client, crate_name).await?;
+
+        if let Some(latest) = &published.latest_stable {
+            let versions = published.versions.iter().map(Version::to_string).collect();
+            self.lock_cache()
+                .insert(&self.registry, crate_name, latest, versions);
+            self.cache_updated.store(true, Ordering::Relaxed);
+        }
+
+        Ok(published)
+    }
+
+    fn cached(&self, crate_name: &str) -> Option<CachedVersion> {
+        self.lock_cache().get(&self.registry, crate_name).cloned()
+    }
+
+    fn lock_cache(&self) -> std::sync::MutexGuard<'_, VersionCache> {
+        // The cache holds plain data, so a panic elsewhere can't leave it inconsistent
+        self.cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Newest cached version of a crate matching `req`
+fn cached_matching(cached: &CachedVersion, req: &VersionReq) -> Option<String> {
+    let versions: Vec<Version> = cached
+        .published
+        .iter()
+        .chain([&cached.version])
+        .filter_map(|version| Version::parse(version).ok())
+        .collect();
+
+    newest_matching(&versions, req).map(Version::to_string)
+}
+
+fn known_good_version(crate_name: &str) -> Option<&'static str> {
+    KNOWN_GOOD_VERSIONS
+        .iter()
+        .find(|(name, _)| *name == crate_name)
+        .map(|(_, version)| *version)
+}
+
+fn warn_fallback(crate_name: &str, resolved: &ResolvedVersion) {
+    let origin = match resolved.source {
+        VersionSource::KnownGood => "godust's known-good version",
+        _ => "the template's fallback version",
+    };
+
+    eprintln!(
+        "⚠️  WARNING: Using fallback version {} for '{}' ({}).",
+        resolved.version, crate_name, origin
+    );
+    eprintln!(
+        "   It may be outdated; check https://crates.io/crates/{} and update the generated Cargo.toml.",
+        crate_name
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(crate_name: &str, pin: Option<&str>) -> DependencyRequest {
+        DependencyRequest {
+            crate_name: crate_name.to_string(),
+            template_fallback: "1.0.0".to_string(),
+            pin: pin.map(|p| p.parse().unwrap()),
+        }
+    }
+
+    #[tokio::test]
+    async fn reports_where_each_offline_version_came_from() {
+        let mut cache = VersionCache::default();
+        cache.insert(
+            &Registry::default(),
+            "godot",
+            "0.4.5",
+            vec!["0.3.1".to_string(), "0.4.5".to_string()],
+        );
+        let resolver = VersionResolver::new(Registry::default(), true, cache).unwrap();
+
+        let report = resolver
+            .resolve_all(vec![
+                request("godot", None),
+                request("bevy_ecs", None),
+                request("serde", None),
+                request("rand", Some("0.9.2")),
+                request("godot", Some("0.3")),
+            ])
+            .await
+            .unwrap();
+
+        let sources: Vec<(&str, &str, VersionSource)> = report
+            .dependencies
+            .iter()
+            .map(|(name, r)| (name.as_str(), r.version.as_str(), r.source))
+            .collect();
+        assert_eq!(
+            sources,
+            [
+                ("godot", "0.4.5", VersionSource::Cache),
+                ("bevy_ecs", "0.17.2", VersionSource::KnownGood),
+                ("serde", "1.0.0", VersionSource::TemplateFallback),
+                ("rand", "0.9.2", VersionSource::Pinned),
+                ("godot", "0.3.1", VersionSource::Cache),
+            ]
+        );
+        assert_eq!(
+            report.summary(),
+            "5 dependencies: 2 cached, 1 pinned, 2 fallback"
+        );
+        assert_eq!(report.get("rand").unwrap().version, "0.9.2");
+    }
+
+    #[tokio::test]
+    async fn unmatched_offline_requirement_is_an_error() {
+        let resolver =
+            VersionResolver::new(Registry::default(), true, VersionCache::default()).unwrap();
+
+        let result = resolver
+            .resolve_all(vec![request("godot", None), request("godot", Some("0.9"))])
+            .await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn unreachable_registry_falls_back() {
+        // Nothing listens on port 1, so every attempt is refused
+        let registry = Registry::parse("http://127.0.0.1:1").unwrap();
+        let resolver = VersionResolver::new(registry, false, VersionCache::default()).unwrap();
+
+        let report = resolver
+            .resolve_all(vec![request("godot", None), request("godot", Some("0.3"))])
+            .await
+            .unwrap();
+
+        assert_eq!(report.count(VersionSource::KnownGood), 2);
+        assert_eq!(report.dependencies[1].1.version, "0.3.0");
+    }
+
+    #[tokio::test]
+    async fn cached_versions_belong_to_their_registry() {
+        let mirror = Registry::parse("sparse+http://mirror.example/index/").unwrap();
+        let cache = || {
+            let mut cache = VersionCache::default();
+            cache.insert(&Registry::default(), "godot", "0.4.5", Vec::new());
+            cache.insert(&mirror, "godot", "0.4.2", Vec::new());
+            cache
+        };
+
+        for (registry, version) in [(Registry::default(), "0.4.5"), (mirror.clone(), "0.4.2")] {
+            let resolver = VersionResolver::new(registry, true, cache()).unwrap();
+            let resolved = resolver.resolve(&request("godot", None)).await.unwrap();
+            assert_eq!(resolved.version, version);
+            assert_eq!(resolved.source, VersionSource::Cache);
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Once;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::error::GodustError;
+use crate::utils::file_plan::PlannedFile;
+
+/// Set once Ctrl-C was pressed after [`catch_interrupts`]
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Turns Ctrl-C into a flag checked by [`Staging::write`] instead of killing
+/// godust on the spot, so the staging directory can be cleaned up
+///
+/// Must be called from within the Tokio runtime.
+pub fn catch_interrupts() {
+    static LISTENER: Once = Once::new();
+    LISTENER.call_once(|| {
+        tokio::spawn(async {
+            while tokio::signal::ctrl_c().await.is_ok() {
+                INTERRUPTED.store(true, Ordering::SeqCst);
+            }
+        });
+    });
+}
+
+/// Whether Ctrl-C was pressed since [`catch_interrupts`]
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+/// Hidden directory a project is generated into before being moved into
+/// place
+///
+/// Dropping it without committing removes it along with everything written
+/// so far, so failed or interrupted generation leaves nothing behind.
+#[derive(Debug)]
+pub struct Staging {
+    dir: PathBuf,
+    committed: bool,
+}
+
+impl Staging {
+    /// Creates a staging directory next to `target`, on the same file system
+    /// so that it can be renamed to it
+    pub fn next_to(target: &Path) -> io::Result<Self> {
+        let parent = target
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        let name = target
+            .file_name()
+            .map(|name| name.to_string_lossy())
+            .unwrap_or_default();
+
+        Self::create(parent.join(format!(".{}.godust-{}", name, std::process::id())))
+    }
+
+    /// Creates a staging directory inside the existing directory `target`
+    pub fn inside(target: &Path) -> io::Result<Self> {
+        Self::create(target.join(format!(".godust-{}", std::process::id())))
+    }
+
+    fn create(dir: PathBuf) -> io::Result<Self> {
+        fs::create_dir(&dir)?;
+        Ok(Self {
+            dir,
+            committed: false,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Writes planned files into the staging directory
+    ///
+    /// Stops with [`GodustError::Interrupted`] as soon as Ctrl-C is pressed.
+    pub fn write(&self, files: &[PlannedFile]) -> Result<(), GodustError> {
+        for file in files {
+            if interrupted() {
+                return Err(GodustError::Interrupted);
+            }
+
+            let dest_file = self.dir.join(&file.path);
+            if let Some(parent) = dest_file.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&dest_file, &file.contents)?;
+        }
+
+        Ok(())
+    }
+
+    /// Renames the staging directory to `target`, which must not exist
+    pub fn commit_as(mut self, target: &Path) -> Result<(), GodustError> {
+        if interrupted() {
+            return Err(GodustError::Interrupted);
+        }
+        if target.exists() {
+            return Err(GodustError::DestinationConflict(vec![target.to_path_buf()]));
+        }
+
+        fs::rename(&self.dir, target)?;
+        self.committed = true;
+        Ok(())
+    }
+
+    /// Moves everything staged into the existing directory `target`
+    ///
+    /// Directories missing from `target` are moved whole and existing ones
+    /// are merged file by file. If anything can't be moved, what was already
+    /// moved is put back and `target` is left as it was.
+    pub fn commit_into(self, target: &Path) -> Result<(), GodustError> {
+        if interrupted() {
+            return Err(GodustError::Interrupted);
+        }
+
+        let mut moved = Vec::new();
+        if let Err(e) = merge_into(&self.dir, target, &mut moved) {
+            for (from, to) in moved.iter().rev() {
+                let _ = fs::rename(to, from);
+            }
+            return Err(e);
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Staging {
+    fn drop(&mut self) {
+        if !self.committed {
+            let _ = fs::remove_dir_all(&self.dir);
+        }
+    }
+}
+
+/// Moves the entries of `from` into `to`, recording every rename
+fn merge_into(
+    from: &Path,
+    to: &Path,
+    moved: &mut Vec<(PathBuf, PathBuf)>,
+) -> Result<(), GodustError> {
+    let mut entries = fs::read_dir(from)?.collect::<io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let source = entry.path();
+        let dest = to.join(entry.file_name());
+
+        if entry.file_type()?.is_dir() && dest.is_dir() {
+            merge_into(&source, &dest, moved)?;
+        } else if dest.symlink_metadata().is_ok() {
+            return Err(GodustError::DestinationConflict(vec![dest]));
+        } else {
+            fs::rename(&source, &dest)?;
+            moved.push((source, dest));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::file_plan::FileOrigin;
+
+    fn planned(path: &str) -> PlannedFile {
+        PlannedFile {
+            path: PathBuf::from(path),
+            contents: path.as_bytes().to_vec(),
+            origin: FileOrigin::Static,
+        }
+    }
+
+    #[test]
+    fn uncommitted_staging_leaves_nothing_behind() {
+        let dir = tempfile::tempdir().unwrap();
+        let parent = dir.path();
+        let target = parent.join("demo");
+
+        let staging = Staging::next_to(&target).unwrap();
+        staging.write(&[planned("demo/project.godot")]).unwrap();
+        drop(staging);
+
+        assert_eq!(fs::read_dir(parent).unwrap().count(), 0);
+
+        let staging = Staging::next_to(&target).unwrap();
+        staging.write(&[planned("demo/project.godot")]).unwrap();
+        staging.commit_as(&target).unwrap();
+        assert!(target.join("demo/project.godot").is_file());
+        assert_eq!(fs::read_dir(parent).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn failed_merge_puts_moved_files_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path();
+        fs::create_dir_all(target.join("game")).unwrap();
+        fs::write(target.join("game/project.godot"), "existing").unwrap();
+
+        // `core/` is moved first, then `game/project.godot` conflicts
+        let staging = Staging::inside(target).unwrap();
+        staging
+            .write(&[planned("core/src/lib.rs"), planned("game/project.godot")])
+            .unwrap();
+        let err = staging.commit_into(target).unwrap_err();
+
+        assert!(matches!(err, GodustError::DestinationConflict(_)));
+        assert!(!target.join("core").exists());
+        assert_eq!(
+            fs::read_to_string(target.join("game/project.godot")).unwrap(),
+            "existing"
+        );
+        assert_eq!(fs::read_dir(target).unwrap().count(), 1);
+
+        let staging = Staging::inside(target).unwrap();
+        staging
+            .write(&[planned("core/src/lib.rs"), planned("game/demo.gdextension")])
+            .unwrap();
+        staging.commit_into(target).unwrap();
+        assert!(target.join("core/src/lib.rs").is_file());
+        assert!(target.join("game/demo.gdextension").is_file());
+        assert!(target.join("game/project.godot").is_file());
+        assert_eq!(fs::read_dir(target).unwrap().count(), 2);
+    }
+}
@@ -0,0 +1,480 @@
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use tera::Context;
+
+use crate::error::GodustError;
+use crate::utils::engine::EngineVersion;
+use crate::utils::template_parser::TemplateSource;
+
+/// Name of the manifest file at the root of every template directory
+///
+/// The manifest is read by godust and never copied into the generated project.
+pub const MANIFEST_FILE: &str = "template.toml";
+
+/// Variables godust provides to every template, the keys of
+/// [`base_context`](crate::generators::base_context)
+///
+/// Templates may declare them to document or validate them, but their values
+/// come from the command line options and can't be set with `--var`.
+pub const BUILTIN_VARIABLES: &[&str] = &[
+    "project_name",
+    "project_kebab_name",
+    "struct_name",
+    "engine_version",
+    "rendering_method",
+    "rendering_method_formatted",
+    "compatibility_minimum",
+    "layout",
+    "godot_dir",
+    "core_dir",
+    "cargo_target_dir",
+    "library_dir",
+    "crates_dir",
+    "workspace_members",
+    "tools",
+    "gdextension_libraries",
+    "gdextension_icons",
+    "gdextension_dependencies",
+];
+
+/// Provided along with the resolved version of the `godot` crate
+pub const GODOT_API_FEATURE: &str = "godot_api_feature";
+
+/// Contents of a `template.toml` file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateManifest {
+    pub name: String,
+    pub description: String,
+    /// Oldest Godot engine version the template works with (e.g. "4.1")
+    #[serde(default)]
+    pub min_engine_version: Option<String>,
+    /// Crates resolved from crates.io into `<crate>_version` variables,
+    /// mapped to the version used when crates.io is unreachable
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, String>,
+    #[serde(default)]
+    pub variables: Vec<VariableSpec>,
+}
+
+/// A variable declared by a template manifest
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VariableSpec {
+    pub name: String,
+    #[serde(rename = "type", default)]
+    pub kind: VariableType,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default)]
+    pub default: Option<toml::Value>,
+    /// Regex the value must match
+    #[serde(default)]
+    pub validation: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VariableType {
+    #[default]
+    String,
+    Bool,
+    Integer,
+    Float,
+}
+
+impl VariableType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VariableType::String => "string",
+            VariableType::Bool => "bool",
+            VariableType::Integer => "integer",
+            VariableType::Float => "float",
+        }
+    }
+
+    /// Converts a value typed on the command line into a context value
+    fn parse(&self, raw: &str) -> Option<tera::Value> {
+        match self {
+            VariableType::String => Some(tera::Value::from(raw)),
+            VariableType::Bool => raw.parse::<bool>().ok().map(tera::Value::from),
+            VariableType::Integer => raw.parse::<i64>().ok().map(tera::Value::from),
+            VariableType::Float => raw.parse::<f64>().ok().map(tera::Value::from),
+        }
+    }
+
+    /// Converts a manifest default into a context value
+    fn convert(&self, value: &toml::Value) -> Option<tera::Value> {
+        match (self, value) {
+            (VariableType::String, toml::Value::String(s)) => Some(tera::Value::from(s.as_str())),
+            (VariableType::Bool, toml::Value::Boolean(b)) => Some(tera::Value::from(*b)),
+            (VariableType::Integer, toml::Value::Integer(i)) => Some(tera::Value::from(*i)),
+            (VariableType::Float, toml::Value::Float(f)) => Some(tera::Value::from(*f)),
+            (VariableType::Float, toml::Value::Integer(i)) => Some(tera::Value::from(*i as f64)),
+            _ => None,
+        }
+    }
+}
+
+impl TemplateManifest {
+    /// Parses and validates the contents of a manifest file
+    pub fn parse(content: &str) -> Result<Self, GodustError> {
+        let manifest: TemplateManifest = toml::from_str(content).map_err(|e| {
+            GodustError::InvalidTemplate(format!("Invalid {}: {}", MANIFEST_FILE, e))
+        })?;
+
+        if let Some(minimum) = &manifest.min_engine_version {
+            minimum.parse::<EngineVersion>().map_err(|e| {
+                GodustError::InvalidTemplate(format!("Invalid min_engine_version: {}", e))
+            })?;
+        }
+
+        for variable in &manifest.variables {
+            if let Some(pattern) = &variable.validation {
+                Regex::new(pattern).map_err(|e| {
+                    GodustError::InvalidTemplate(format!(
+                        "Invalid validation regex for variable '{}': {}",
+                        variable.name, e
+                    ))
+                })?;
+            }
+
+            if let Some(default) = &variable.default
+                && variable.kind.convert(default).is_none()
+            {
+                return Err(GodustError::InvalidTemplate(format!(
+                    "Default value of variable '{}' is not a {}",
+                    variable.name,
+                    variable.kind.as_str()
+                )));
+            }
+        }
+
+        Ok(manifest)
+    }
+
+    /// Reads the manifest of a template, if it has one
+    pub fn load(source: &TemplateSource) -> Result<Option<Self>, GodustError> {
+        let content = match source {
+            TemplateSource::Embedded(dir) => match dir.get_file(dir.path().join(MANIFEST_FILE)) {
+                Some(file) => std::str::from_utf8(file.contents())
+                    .map_err(|e| {
+                        GodustError::InvalidTemplate(format!("Invalid {}: {}", MANIFEST_FILE, e))
+                    })?
+                    .to_string(),
+                None => return Ok(None),
+            },
+            TemplateSource::Local(dir) => {
+                let path = dir.join(MANIFEST_FILE);
+                if !path.is_file() {
+                    return Ok(None);
+                }
+                fs::read_to_string(path)?
+            }
+        };
+
+        Self::parse(&content).map(Some)
+    }
+
+    /// Manifest assumed for local templates that don't ship one
+    ///
+    /// Only resolves the `godot` crate, which is enough for templates derived
+    /// from "blank".
+    pub fn local_default(name: &str) -> Self {
+        TemplateManifest {
+            name: name.to_string(),
+            description: "Template loaded from a local directory".to_string(),
+            min_engine_version: None,
+            dependencies: BTreeMap::from([("godot".to_string(), "0.4.0".to_string())]),
+            variables: Vec::new(),
+        }
+    }
+
+    /// Context variable holding the resolved version of `crate_name`
+    pub fn dependency_variable(crate_name: &str) -> String {
+        format!("{}_version", crate_name.replace('-', "_"))
+    }
+
+    /// Every variable godust provides to this template: the
+    /// [`BUILTIN_VARIABLES`], the resolved dependency versions and, when it
+    /// depends on `godot`, `godot_api_feature`
+    pub fn builtin_variables(&self) -> Vec<String> {
+        let mut names: Vec<String> = BUILTIN_VARIABLES.iter().map(|v| v.to_string()).collect();
+        names.extend(
+            self.dependencies
+                .keys()
+                .map(|name| Self::dependency_variable(name)),
+        );
+        if self.dependencies.contains_key("godot") {
+            names.push(GODOT_API_FEATURE.to_string());
+        }
+        names
+    }
+
+    /// Fails when `engine_version` is older than `min_engine_version`
+    pub fn check_engine_version(&self, engine_version: &EngineVersion) -> Result<(), GodustError> {
+        let Some(minimum) = &self.min_engine_version else {
+            return Ok(());
+        };
+
+        let minimum_version = minimum.parse().map_err(GodustError::InvalidTemplate)?;
+        if !engine_version.is_at_least(&minimum_version) {
+            return Err(GodustError::InvalidTemplate(format!(
+                "Template '{}' requires Godot {} or newer, but engine version {} was requested",
+                self.name, minimum, engine_version
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Adds the declared variables to `context`
+    ///
+    /// Values come from `provided` (the `--var KEY=VALUE` options) or the
+    /// manifest defaults. Built-in variables must already be in the context
+    /// and are only validated.
+    pub fn resolve_variables(
+        &self,
+        context: &mut Context,
+        provided: &HashMap<String, String>,
+    ) -> Result<(), GodustError> {
+        let builtin = self.builtin_variables();
+        for name in provided.keys() {
+            if builtin.contains(name) {
+                return Err(GodustError::InvalidTemplate(format!(
+                    "Variable '{}' is set by godust and cannot be passed with --var",
+                    name
+                )));
+            }
+            if !self.variables.iter().any(|v| &v.name == name) {
+                return Err(GodustError::InvalidTemplate(format!(
+                    "Template '{}' has no variable named '{}'",
+                    self.name, name
+                )));
+            }
+        }
+
+        for variable in &self.variables {
+            let value = if builtin.contains(&variable.name) {
+                context.get(&variable.name).cloned()
+            } else if let Some(raw) = provided.get(&variable.name) {
+                let value = variable.kind.parse(raw).ok_or_else(|| {
+                    GodustError::InvalidTemplate(format!(
+                        "Value '{}' for variable '{}' is not a {}",
+                        raw,
+                        variable.name,
+                        variable.kind.as_str()
+                    ))
+                })?;
+                Some(value)
+            } else {
+                variable
+                    .default
+                    .as_ref()
+                    .and_then(|default| variable.kind.convert(default))
+            };
+
+            let Some(value) = value else {
+                if variable.required {
+                    return Err(GodustError::InvalidTemplate(format!(
+                        "Template '{}' requires variable '{}' (pass it with --var {}=<{}>)",
+                        self.name,
+                        variable.name,
+                        variable.name,
+                        variable.kind.as_str()
+                    )));
+                }
+                continue;
+            };
+
+            if let Some(pattern) = &variable.validation {
+                let text = match &value {
+                    tera::Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                let regex =
+                    Regex::new(pattern).map_err(|e| GodustError::InvalidTemplate(e.to_string()))?;
+                if !regex.is_match(&text) {
+                    return Err(GodustError::InvalidTemplate(format!(
+                        "Value '{}' for variable '{}' does not match '{}'",
+                        text, variable.name, pattern
+                    )));
+                }
+            }
+
+            context.insert(variable.name.as_str(), &value);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"
+name = "sample"
+description = "Sample template"
+min_engine_version = "4.2"
+
+[dependencies]
+godot = "0.1.0"
+bevy-ecs = "0.14.0"
+
+[[variables]]
+name = "project_name"
+required = true
+validation = "^[a-z_]+$"
+
+[[variables]]
+name = "entity_count"
+type = "integer"
+default = 100
+
+[[variables]]
+name = "author"
+required = true
+"#;
+
+    fn base() -> Context {
+        let mut context = Context::new();
+        context.insert("project_name", "my_game");
+        context
+    }
+
+    fn provided(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn embedded_manifests_are_valid() {
+        for dir in crate::TEMPLATES.dirs() {
+            let manifest = TemplateManifest::load(&TemplateSource::Embedded(dir))
+                .unwrap()
+                .unwrap_or_else(|| panic!("{} has no {}", dir.path().display(), MANIFEST_FILE));
+            assert_eq!(Some(manifest.name.as_str()), dir.path().to_str());
+        }
+    }
+
+    #[test]
+    fn resolves_defaults_and_provided_values() {
+        let manifest = TemplateManifest::parse(MANIFEST).unwrap();
+        let mut context = base();
+        manifest
+            .resolve_variables(&mut context, &provided(&[("author", "Ada")]))
+            .unwrap();
+
+        assert_eq!(context.get("entity_count"), Some(&tera::Value::from(100)));
+        assert_eq!(context.get("author"), Some(&tera::Value::from("Ada")));
+    }
+
+    #[test]
+    fn rejects_missing_unknown_and_mistyped_variables() {
+        let manifest = TemplateManifest::parse(MANIFEST).unwrap();
+
+        assert!(
+            manifest
+                .resolve_variables(&mut base(), &provided(&[]))
+                .is_err()
+        );
+        assert!(
+            manifest
+                .resolve_variables(&mut base(), &provided(&[("author", "a"), ("typo", "1")]))
+                .is_err()
+        );
+        assert!(
+            manifest
+                .resolve_variables(
+                    &mut base(),
+                    &provided(&[("author", "a"), ("entity_count", "many")])
+                )
+                .is_err()
+        );
+        assert!(
+            manifest
+                .resolve_variables(
+                    &mut base(),
+                    &provided(&[("author", "a"), ("project_name", "other")])
+                )
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn rejects_every_builtin_variable_passed_with_var() {
+        let manifest = TemplateManifest::parse(MANIFEST).unwrap();
+        let builtin = manifest.builtin_variables();
+        assert!(builtin.contains(&"bevy_ecs_version".to_string()));
+        assert!(builtin.contains(&"godot_api_feature".to_string()));
+
+        for name in &builtin {
+            let error = manifest
+                .resolve_variables(&mut base(), &provided(&[("author", "a"), (name, "x")]))
+                .unwrap_err();
+            assert!(
+                error.to_string().contains("cannot be passed with --var"),
+                "--var {}: {}",
+                name,
+                error
+            );
+        }
+    }
+
+    #[test]
+    fn validates_builtin_variables() {
+        let manifest = TemplateManifest::parse(MANIFEST).unwrap();
+        let mut context = Context::new();
+        context.insert("project_name", "Not Valid");
+
+        assert!(
+            manifest
+                .resolve_variables(&mut context, &provided(&[("author", "a")]))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn checks_minimum_engine_version() {
+        let manifest = TemplateManifest::parse(MANIFEST).unwrap();
+
+        let check = |raw: &str| manifest.check_engine_version(&raw.parse().unwrap());
+
+        assert!(check("4.2").is_ok());
+        assert!(check("4.10.1").is_ok());
+        assert!(check("4.1").is_err());
+    }
+
+    #[test]
+    fn dependency_variable_names_are_identifiers() {
+        assert_eq!(
+            TemplateManifest::dependency_variable("godot"),
+            "godot_version"
+        );
+        assert_eq!(
+            TemplateManifest::dependency_variable("bevy-ecs"),
+            "bevy_ecs_version"
+        );
+    }
+
+    #[test]
+    fn rejects_defaults_of_the_wrong_type() {
+        let manifest = r#"
+name = "bad"
+description = "Bad template"
+
+[[variables]]
+name = "count"
+type = "integer"
+default = "ten"
+"#;
+        assert!(TemplateManifest::parse(manifest).is_err());
+    }
+}
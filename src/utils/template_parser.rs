@@ -1,20 +1,44 @@
+use include_dir::Dir;
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 use tera::{Context, Tera, Value};
-use include_dir::{Dir, File};
+use walkdir::WalkDir;
+
+use crate::error::GodustError;
+use crate::utils::file_plan::{FileOrigin, PlannedFile};
+use crate::utils::template_manifest::MANIFEST_FILE;
+use crate::utils::uid_generator::{self, UidGenerator};
+
+/// Where the files of a template are read from
+///
+/// Both sources follow the same rules: `{{var}}` in file and directory names
+/// is replaced from the context, `.tera` files are rendered (and lose their
+/// extension) and every other file is copied as-is.
+#[derive(Debug, Clone)]
+pub enum TemplateSource {
+    /// Template embedded in the binary at compile time
+    Embedded(&'static Dir<'static>),
+    /// Template directory on disk
+    Local(PathBuf),
+}
 
-use crate::utils::uid_generator;
+#[derive(Debug)]
+struct TemplateFile {
+    relative_path: PathBuf,
+    contents: Cow<'static, [u8]>,
+}
 
 #[derive(Debug)]
-struct TemplateStructure<'a> {
-    tera_files: Vec<&'a File<'a>>,
-    static_files: Vec<&'a File<'a>>,
-    relative_paths: HashMap<String, PathBuf>,
+struct TemplateStructure {
+    tera_files: Vec<TemplateFile>,
+    static_files: Vec<TemplateFile>,
 }
 
-fn substitute_path_variables(path: &Path, context: &Context) -> Result<PathBuf, io::Error> {
+fn substitute_path_variables(path: &Path, context: &Context) -> Result<PathBuf, GodustError> {
     let mut result = PathBuf::new();
 
     for component in path.components() {
@@ -31,14 +55,11 @@ fn substitute_path_variables(path: &Path, context: &Context) -> Result<PathBuf,
                             .get(var_name)
                             .and_then(|v| v.as_str())
                             .ok_or_else(|| {
-                                io::Error::new(
-                                    io::ErrorKind::Other,
-                                    format!(
-                                        "Variable '{}' not found in context for path: {}",
-                                        var_name,
-                                        path.display()
-                                    ),
-                                )
+                                GodustError::TemplateRender(format!(
+                                    "Variable '{}' not found in context for path: {}",
+                                    var_name,
+                                    path.display()
+                                ))
                             })?;
 
                     substituted = format!(
@@ -52,303 +73,470 @@ fn substitute_path_variables(path: &Path, context: &Context) -> Result<PathBuf,
                 }
             }
 
-            result.push(substituted);
+            // A variable may expand to several components, or to none for the root
+            if !substituted.is_empty() {
+                result.push(substituted);
+            }
         } else {
-            return Err(io::Error::new(
-                io::ErrorKind::Other,
-                format!("Invalid path component (non-UTF8): {}", path.display()),
-            ));
+            return Err(GodustError::TemplateRender(format!(
+                "Invalid path component (non-UTF8): {}",
+                path.display()
+            )));
         }
     }
 
     Ok(result)
 }
 
-pub fn parse_template(
-    template_dir: &Dir,
-    dest_path: &Path,
-    context: Context,
-) -> Result<(), io::Error> {
-    let template_structure = discover_template_structure(template_dir)?;
+/// Renders every file of `source` in memory, without touching the disk
+pub fn plan_template(
+    source: &TemplateSource,
+    context: &Context,
+    uids: UidGenerator,
+) -> Result<Vec<PlannedFile>, GodustError> {
+    plan_template_filtered(source, context, uids, |_| true)
+}
 
-    validate_destination(dest_path, &template_structure, &context)?;
+/// Renders the files of `source` whose destination `include` accepts in
+/// memory, with their paths relative to the destination
+pub fn plan_template_filtered(
+    source: &TemplateSource,
+    context: &Context,
+    uids: UidGenerator,
+    include: impl Fn(&Path) -> bool,
+) -> Result<Vec<PlannedFile>, GodustError> {
+    let mut template_structure = discover_template_structure(source)?;
+    for files in [
+        &mut template_structure.tera_files,
+        &mut template_structure.static_files,
+    ] {
+        let mut kept = Vec::new();
+        for file in files.drain(..) {
+            if include(&relative_destination(&file, context)?) {
+                kept.push(file);
+            }
+        }
+        *files = kept;
+    }
 
-    let tera = init_tera_engine(template_dir)?;
+    let mut tera = init_tera_engine(&template_structure)?;
+    let mut planned = Vec::new();
 
     for tera_file in &template_structure.tera_files {
-        let file_path = tera_file.path();
-        let path_str = file_path.to_str().ok_or_else(|| {
-            io::Error::new(
-                io::ErrorKind::Other,
-                format!("Invalid UTF-8 in path: {:?}", file_path),
-            )
+        let template_name = template_name(&tera_file.relative_path)?;
+        let path = relative_destination(tera_file, context)?;
+        // Separators are normalized so seeded UIDs are the same on every platform
+        let uid_path = path.to_string_lossy().replace('\\', "/");
+        tera.register_function("generate_uid", generate_uid_function(uids, uid_path));
+        let rendered = tera.render(&template_name, context).map_err(|e| {
+            GodustError::TemplateRender(format!(
+                "Failed to render template '{}': {}",
+                template_name, e
+            ))
         })?;
 
-        let relative_path = template_structure
-            .relative_paths
-            .get(path_str)
-            .ok_or_else(|| {
-                io::Error::new(
-                    io::ErrorKind::Other,
-                    format!("Missing relative path for: {}", path_str),
-                )
-            })?;
+        check_uids(&path, rendered.as_bytes())?;
+        planned.push(PlannedFile {
+            path,
+            contents: rendered.into_bytes(),
+            origin: FileOrigin::Rendered,
+        });
+    }
 
-        let substituted_path = substitute_path_variables(relative_path, &context)?;
-
-        let dest_file = dest_path.join(
-            substituted_path
-                .to_str()
-                .and_then(|s| s.strip_suffix(".tera"))
-                .ok_or_else(|| {
-                    io::Error::new(
-                        io::ErrorKind::Other,
-                        format!("Invalid .tera filename: {}", substituted_path.display()),
-                    )
-                })?,
-        );
+    for static_file in &template_structure.static_files {
+        let path = relative_destination(static_file, context)?;
+        check_uids(&path, &static_file.contents)?;
+        planned.push(PlannedFile {
+            path,
+            contents: static_file.contents.to_vec(),
+            origin: FileOrigin::Static,
+        });
+    }
+
+    Ok(planned)
+}
+
+/// Fails when a `uid="..."` attribute of a scene or resource isn't a UID
+/// Godot can read, which it would silently drop on load
+fn check_uids(path: &Path, contents: &[u8]) -> Result<(), GodustError> {
+    let Ok(text) = std::str::from_utf8(contents) else {
+        return Ok(());
+    };
+
+    for attribute in text.split("uid=\"").skip(1) {
+        let uid = attribute.split('"').next().unwrap_or_default();
+        if uid_generator::text_to_id(uid).is_none() {
+            return Err(GodustError::InvalidTemplate(format!(
+                "{} has an invalid UID '{}' (use uid://{{{{ generate_uid() }}}} instead)",
+                path.display(),
+                uid
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Paths of the planned files that already exist in `dest_path`
+pub fn conflicts(dest_path: &Path, files: &[PlannedFile]) -> Vec<PathBuf> {
+    files
+        .iter()
+        .map(|file| dest_path.join(&file.path))
+        .filter(|path| path.exists())
+        .collect()
+}
 
-        render_and_write_file(&tera, &dest_file, &context, relative_path)?;
+/// Path of a template file in the output, relative to the destination
+///
+/// Path variables are substituted and `.tera` files lose their extension.
+fn relative_destination(file: &TemplateFile, context: &Context) -> Result<PathBuf, GodustError> {
+    let substituted_path = substitute_path_variables(&file.relative_path, context)?;
+
+    if !is_tera_file(&file.relative_path) {
+        return Ok(substituted_path);
     }
 
-    for static_file in &template_structure.static_files {
-        let file_path = static_file.path();
-        let path_str = file_path.to_str().ok_or_else(|| {
-            io::Error::new(
-                io::ErrorKind::Other,
-                format!("Invalid UTF-8 in path: {:?}", file_path),
-            )
+    let stripped = substituted_path
+        .to_str()
+        .and_then(|s| s.strip_suffix(".tera"))
+        .ok_or_else(|| {
+            GodustError::TemplateRender(format!(
+                "Invalid .tera filename: {}",
+                substituted_path.display()
+            ))
         })?;
 
-        let relative_path = template_structure
-            .relative_paths
-            .get(path_str)
-            .ok_or_else(|| {
-                io::Error::new(
-                    io::ErrorKind::Other,
-                    format!("Missing relative path for: {}", path_str),
-                )
-            })?;
+    Ok(PathBuf::from(stripped))
+}
 
-        let substituted_path = substitute_path_variables(relative_path, &context)?;
-        let dest_file = dest_path.join(substituted_path);
+fn is_tera_file(path: &Path) -> bool {
+    path.extension().and_then(|s| s.to_str()) == Some("tera")
+}
 
-        if let Some(parent) = dest_file.parent() {
-            fs::create_dir_all(parent)?;
-        }
+fn discover_template_structure(source: &TemplateSource) -> Result<TemplateStructure, GodustError> {
+    let files = match source {
+        TemplateSource::Embedded(template_dir) => embedded_files(template_dir),
+        TemplateSource::Local(template_dir) => local_files(template_dir)?,
+    };
 
-        // Write embedded file contents to destination
-        fs::write(&dest_file, static_file.contents())?;
-    }
+    // The manifest describes the template and is not part of the output
+    let (tera_files, static_files) = files
+        .into_iter()
+        .filter(|file| file.relative_path != Path::new(MANIFEST_FILE))
+        .partition(|file| is_tera_file(&file.relative_path));
 
-    Ok(())
+    Ok(TemplateStructure {
+        tera_files,
+        static_files,
+    })
 }
 
-fn discover_template_structure<'a>(template_dir: &'a Dir<'a>) -> Result<TemplateStructure<'a>, io::Error> {
-    let mut tera_files = Vec::new();
-    let mut static_files = Vec::new();
-    let mut relative_paths = HashMap::new();
+fn embedded_files(template_dir: &'static Dir<'static>) -> Vec<TemplateFile> {
+    let mut files = Vec::new();
 
     // Get the base path that we need to strip from all file paths
     // This is the template directory name (e.g., "blank" or "blank_ecs")
     let base_prefix = template_dir.path();
 
     // Recursively traverse embedded directory
-    fn traverse_dir<'a>(
-        dir: &'a Dir,
-        tera_files: &mut Vec<&'a File<'a>>,
-        static_files: &mut Vec<&'a File<'a>>,
-        relative_paths: &mut HashMap<String, PathBuf>,
-        base_prefix: &Path,
-    ) {
+    fn traverse_dir(dir: &'static Dir<'static>, files: &mut Vec<TemplateFile>, base_prefix: &Path) {
         for file in dir.files() {
             let file_path = file.path();
-            let path_str = file_path.to_str().unwrap_or("");
-
-            if file_path.extension().and_then(|s| s.to_str()) == Some("tera") {
-                tera_files.push(file);
-            } else {
-                static_files.push(file);
-            }
 
             // Strip the template directory prefix to get relative path
-            let relative_path = file_path.strip_prefix(base_prefix)
-                .unwrap_or(file_path);
+            let relative_path = file_path.strip_prefix(base_prefix).unwrap_or(file_path);
 
-            relative_paths.insert(path_str.to_string(), relative_path.to_path_buf());
+            files.push(TemplateFile {
+                relative_path: relative_path.to_path_buf(),
+                contents: Cow::Borrowed(file.contents()),
+            });
         }
 
         for subdir in dir.dirs() {
-            traverse_dir(subdir, tera_files, static_files, relative_paths, base_prefix);
+            traverse_dir(subdir, files, base_prefix);
         }
     }
 
-    traverse_dir(template_dir, &mut tera_files, &mut static_files, &mut relative_paths, base_prefix);
+    traverse_dir(template_dir, &mut files, base_prefix);
 
-    Ok(TemplateStructure {
-        tera_files,
-        static_files,
-        relative_paths,
-    })
+    files
 }
 
-fn init_tera_engine(template_dir: &Dir) -> Result<Tera, io::Error> {
-    let mut tera = Tera::default();
+fn local_files(template_dir: &Path) -> Result<Vec<TemplateFile>, GodustError> {
+    if !template_dir.is_dir() {
+        return Err(GodustError::Usage(format!(
+            "Template directory does not exist: {}",
+            template_dir.display()
+        )));
+    }
 
-    // Get the base path to strip from template names
-    let base_prefix = template_dir.path();
+    let mut files = Vec::new();
 
-    // Recursively add all .tera files to the engine
-    fn add_tera_files(dir: &Dir, tera: &mut Tera, base_prefix: &Path) -> Result<(), io::Error> {
-        for file in dir.files() {
-            let file_path = file.path();
-            if file_path.extension().and_then(|s| s.to_str()) == Some("tera") {
-                // Strip the template directory prefix to get relative path
-                let relative_path = file_path.strip_prefix(base_prefix)
-                    .unwrap_or(file_path);
-
-                let template_name = relative_path.to_str().ok_or_else(|| {
-                    io::Error::new(
-                        io::ErrorKind::Other,
-                        format!("Invalid UTF-8 in template path: {:?}", relative_path),
-                    )
-                })?.replace('\\', "/");
-
-                let content = std::str::from_utf8(file.contents()).map_err(|e| {
-                    io::Error::new(
-                        io::ErrorKind::Other,
-                        format!("Invalid UTF-8 in template file '{}': {}", template_name, e),
-                    )
-                })?;
-
-                tera.add_raw_template(&template_name, content).map_err(|e| {
-                    io::Error::new(
-                        io::ErrorKind::Other,
-                        format!("Failed to add template '{}': {}", template_name, e),
-                    )
-                })?;
-            }
+    for entry in WalkDir::new(template_dir).sort_by_file_name() {
+        let entry = entry.map_err(io::Error::other)?;
+        if !entry.file_type().is_file() {
+            continue;
         }
 
-        for subdir in dir.dirs() {
-            add_tera_files(subdir, tera, base_prefix)?;
-        }
+        // Strip the template directory prefix to get relative path
+        let relative_path = entry
+            .path()
+            .strip_prefix(template_dir)
+            .map_err(io::Error::other)?;
 
-        Ok(())
+        files.push(TemplateFile {
+            relative_path: relative_path.to_path_buf(),
+            contents: Cow::Owned(fs::read(entry.path())?),
+        });
     }
 
-    add_tera_files(template_dir, &mut tera, base_prefix)?;
+    Ok(files)
+}
+
+fn template_name(relative_path: &Path) -> Result<String, GodustError> {
+    let name = relative_path.to_str().ok_or_else(|| {
+        GodustError::TemplateRender(format!(
+            "Invalid template path (non-UTF8): {}",
+            relative_path.display()
+        ))
+    })?;
+
+    Ok(name.replace('\\', "/"))
+}
+
+fn init_tera_engine(template_structure: &TemplateStructure) -> Result<Tera, GodustError> {
+    let mut tera = Tera::default();
+
+    // Add all .tera files to the engine, named by their relative path
+    for file in &template_structure.tera_files {
+        let template_name = template_name(&file.relative_path)?;
 
-    tera.register_function("generate_uid", generate_uid_function);
+        let content = std::str::from_utf8(&file.contents).map_err(|e| {
+            GodustError::TemplateRender(format!(
+                "Invalid UTF-8 in template file '{}': {}",
+                template_name, e
+            ))
+        })?;
+
+        tera.add_raw_template(&template_name, content)
+            .map_err(|e| {
+                GodustError::TemplateRender(format!(
+                    "Failed to add template '{}': {}",
+                    template_name, e
+                ))
+            })?;
+    }
 
     Ok(tera)
 }
 
-fn generate_uid_function(_args: &HashMap<String, Value>) -> tera::Result<Value> {
-    let uid = uid_generator::generate_godot_uid();
-    let uid_without_prefix = uid.trim_start_matches("uid://");
-    Ok(Value::String(uid_without_prefix.to_string()))
+/// Tera `generate_uid()`: a Godot UID without the `uid://` prefix
+///
+/// Registered again for every rendered file so seeded UIDs depend on the
+/// path the file is written to, relative to the destination, and not on how
+/// the template directories are named.
+fn generate_uid_function(uids: UidGenerator, path: String) -> impl tera::Function {
+    let count = AtomicU64::new(0);
+    move |_args: &HashMap<String, Value>| {
+        let index = count.fetch_add(1, Ordering::Relaxed);
+        let uid = uid_generator::id_to_text(uids.id_for(&path, index));
+        Ok(Value::String(uid.trim_start_matches("uid://").to_string()))
+    }
 }
 
-fn validate_destination(
-    dest_path: &Path,
-    template_structure: &TemplateStructure,
-    context: &Context,
-) -> Result<(), io::Error> {
-    if !dest_path.exists() {
-        return Err(io::Error::new(
-            io::ErrorKind::NotFound,
-            format!(
-                "Destination directory does not exist: {}",
-                dest_path.display()
-            ),
-        ));
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_context() -> Context {
+        let mut context = Context::new();
+        context.insert("project_name", "my_game");
+        context.insert("struct_name", "MyGame");
+        context
     }
 
-    let mut conflicts = Vec::new();
+    #[test]
+    fn substitutes_variables_in_path_components() {
+        let path = Path::new("{{project_name}}_core/src/{{ project_name }}.rs.tera");
+        let substituted = substitute_path_variables(path, &test_context()).unwrap();
+        assert_eq!(substituted, Path::new("my_game_core/src/my_game.rs.tera"));
+    }
 
-    for tera_file in &template_structure.tera_files {
-        let file_path = tera_file.path();
-        let path_str = file_path.to_str().unwrap_or("");
-
-        if let Some(relative_path) = template_structure.relative_paths.get(path_str) {
-            let substituted_path = substitute_path_variables(relative_path, context)?;
-
-            if let Some(relative_str) = substituted_path.to_str() {
-                if let Some(stripped) = relative_str.strip_suffix(".tera") {
-                    let dest_file = dest_path.join(stripped);
-                    if dest_file.exists() {
-                        conflicts.push(dest_file);
-                    }
-                }
-            }
-        }
+    #[test]
+    fn path_variables_can_be_nested_or_empty() {
+        let mut context = test_context();
+        context.insert("godot_dir", "");
+        context.insert("core_dir", "rust/core");
+
+        let godot = substitute_path_variables(Path::new("{{godot_dir}}/icon.svg"), &context);
+        let core = substitute_path_variables(Path::new("{{core_dir}}/src/lib.rs"), &context);
+        assert_eq!(godot.unwrap(), Path::new("icon.svg"));
+        assert_eq!(core.unwrap(), Path::new("rust/core/src/lib.rs"));
     }
 
-    for static_file in &template_structure.static_files {
-        let file_path = static_file.path();
-        let path_str = file_path.to_str().unwrap_or("");
+    #[test]
+    fn missing_path_variable_is_an_error() {
+        let path = Path::new("{{unknown}}/lib.rs");
+        assert!(substitute_path_variables(path, &test_context()).is_err());
+    }
 
-        if let Some(relative_path) = template_structure.relative_paths.get(path_str) {
-            let substituted_path = substitute_path_variables(relative_path, context)?;
+    fn contents(files: &[PlannedFile], path: &str) -> String {
+        let file = files.iter().find(|file| file.path == Path::new(path));
+        String::from_utf8(file.unwrap().contents.clone()).unwrap()
+    }
 
-            let dest_file = dest_path.join(substituted_path);
-            if dest_file.exists() {
-                conflicts.push(dest_file);
-            }
-        }
+    #[test]
+    fn renders_local_template_directory() {
+        let template = tempfile::tempdir().unwrap();
+        let dest = tempfile::tempdir().unwrap();
+
+        let src = template.path().join("{{project_name}}_core/src");
+        fs::create_dir_all(&src).unwrap();
+        fs::write(src.join("lib.rs.tera"), "struct {{ struct_name }};\n").unwrap();
+        fs::write(template.path().join("README.md"), "{{ not rendered }}").unwrap();
+
+        let files = plan_template(
+            &TemplateSource::Local(template.path().to_path_buf()),
+            &test_context(),
+            UidGenerator::Random,
+        )
+        .unwrap();
+
+        assert_eq!(
+            contents(&files, "my_game_core/src/lib.rs"),
+            "struct MyGame;\n"
+        );
+        assert_eq!(contents(&files, "README.md"), "{{ not rendered }}");
+        assert_eq!(files[0].origin, FileOrigin::Rendered);
+
+        // Existing files are reported before anything is written
+        assert!(conflicts(dest.path(), &files).is_empty());
+        fs::write(dest.path().join("README.md"), "existing").unwrap();
+        assert_eq!(
+            conflicts(dest.path(), &files),
+            [dest.path().join("README.md")]
+        );
     }
 
-    if !conflicts.is_empty() {
-        let conflict_list: Vec<String> = conflicts
-            .iter()
-            .map(|p| format!("  - {}", p.display()))
-            .collect();
-
-        return Err(io::Error::new(
-            io::ErrorKind::AlreadyExists,
-            format!(
-                "Destination has conflicting files:\n{}",
-                conflict_list.join("\n")
-            ),
-        ));
+    #[test]
+    fn skipped_files_are_not_planned() {
+        let template = tempfile::tempdir().unwrap();
+        fs::write(template.path().join("project.godot"), "new").unwrap();
+        fs::write(
+            template.path().join("{{project_name}}.gdextension.tera"),
+            "",
+        )
+        .unwrap();
+
+        let files = plan_template_filtered(
+            &TemplateSource::Local(template.path().to_path_buf()),
+            &test_context(),
+            UidGenerator::Random,
+            |path| path.extension().is_some_and(|ext| ext == "gdextension"),
+        )
+        .unwrap();
+
+        let paths: Vec<&Path> = files.iter().map(|file| file.path.as_path()).collect();
+        assert_eq!(paths, [Path::new("my_game.gdextension")]);
     }
 
-    Ok(())
-}
+    #[test]
+    fn rejects_invalid_uids() {
+        let template = tempfile::tempdir().unwrap();
+        let scene = template.path().join("main.tscn");
+        let plan = || {
+            plan_template(
+                &TemplateSource::Local(template.path().to_path_buf()),
+                &test_context(),
+                UidGenerator::Random,
+            )
+        };
 
-fn render_and_write_file(
-    tera: &Tera,
-    // template_file: &Path,
-    dest_file: &Path,
-    context: &Context,
-    relative_path: &Path,
-) -> Result<(), io::Error> {
-    if let Some(parent) = dest_file.parent() {
-        fs::create_dir_all(parent)?;
+        fs::write(&scene, "[gd_scene format=3 uid=\"uid://b8ypxe8t2fan3\"]\n").unwrap();
+        assert!(plan().is_ok());
+
+        fs::write(&scene, "[gd_scene format=3 uid=\"uid://TODO\"]\n").unwrap();
+        let error = plan().unwrap_err();
+        assert_eq!(error.exit_code(), 5);
+        assert!(error.to_string().contains("'uid://TODO'"), "{}", error);
     }
 
-    let template_name = relative_path
-        .to_str()
-        .ok_or_else(|| {
-            io::Error::new(
-                io::ErrorKind::Other,
-                format!(
-                    "Invalid template path (non-UTF8): {}",
-                    relative_path.display()
-                ),
+    #[test]
+    fn seeded_uids_give_identical_output() {
+        let template = tempfile::tempdir().unwrap();
+        fs::write(
+            template.path().join("Main.tscn.tera"),
+            "uid://{{ generate_uid() }} uid://{{ generate_uid() }}\n",
+        )
+        .unwrap();
+
+        let render = |uids: UidGenerator| {
+            let files = plan_template(
+                &TemplateSource::Local(template.path().to_path_buf()),
+                &test_context(),
+                uids,
             )
-        })?
-        .replace('\\', "/");
+            .unwrap();
+            contents(&files, "Main.tscn")
+        };
 
-    let rendered = tera.render(&template_name, context).map_err(|e| {
-        io::Error::new(
-            io::ErrorKind::Other,
-            format!("Failed to render template '{}': {}", template_name, e),
+        let first = render(UidGenerator::Seeded(7));
+        assert_eq!(first, render(UidGenerator::Seeded(7)));
+        assert_ne!(first, render(UidGenerator::Seeded(8)));
+
+        let uids: Vec<&str> = first.split_whitespace().collect();
+        assert_ne!(uids[0], uids[1]);
+        assert!(uid_generator::text_to_id(uids[0]).is_some());
+    }
+
+    #[test]
+    fn seeded_uids_of_the_blank_template_are_stable() {
+        let mut context = test_context();
+        context.insert("godot_dir", "my-game");
+        context.insert("core_dir", "my_game_core");
+
+        let files = plan_template_filtered(
+            &TemplateSource::Embedded(crate::get_embedded_template("blank").unwrap()),
+            &context,
+            UidGenerator::Seeded(42),
+            |path| path == Path::new("my-game/Main.tscn"),
         )
-    })?;
+        .unwrap();
+
+        // Changing how UIDs are derived breaks reproducibility of existing
+        // projects
+        let scene = contents(&files, "my-game/Main.tscn");
+        assert_eq!(
+            scene.lines().next(),
+            Some("[gd_scene format=3 uid=\"uid://b8ypxe8t2fan3\"]")
+        );
+    }
 
-    fs::write(dest_file, rendered)?;
+    #[test]
+    fn renaming_template_directories_keeps_seeded_uids() {
+        let mut context = test_context();
+        context.insert("godot_dir", "my-game");
+        context.insert("project_kebab_name", "my-game");
+
+        let render = |dir: &str| {
+            let template = tempfile::tempdir().unwrap();
+            fs::create_dir_all(template.path().join(dir)).unwrap();
+            fs::write(
+                template.path().join(dir).join("Main.tscn.tera"),
+                "uid://{{ generate_uid() }}\n",
+            )
+            .unwrap();
 
-    Ok(())
+            plan_template(
+                &TemplateSource::Local(template.path().to_path_buf()),
+                &context,
+                UidGenerator::Seeded(7),
+            )
+            .unwrap()
+        };
+
+        let before = render("{{project_kebab_name}}");
+        let after = render("{{godot_dir}}");
+        assert_eq!(before[0].path, Path::new("my-game/Main.tscn"));
+        assert_eq!(before[0].contents, after[0].contents);
+    }
 }
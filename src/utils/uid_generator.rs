@@ -1,13 +1,175 @@
-use rand::rng;
-
-pub fn generate_godot_uid() -> String {
-    use rand::Rng;
-    let mut rng = rng();
-    let chars: String = (0..13)
-        .map(|_| {
-            let chars = "abcdefghijklmnopqrstuvwxyz0123456789";
-            chars.chars().nth(rng.random_range(0..chars.len())).unwrap()
-        })
-        .collect();
-    format!("uid://{}", chars)
+// Godot resource UIDs
+//
+// Mirrors `ResourceUID` from the engine: a UID is a non-negative 63-bit
+// integer written as `uid://` followed by the integer in base 34, most
+// significant digit first, using `a`-`y` for 0-24 and `0`-`8` for 25-33.
+
+use rand::Rng;
+
+const PREFIX: &str = "uid://";
+
+/// Number of letters used as digits (`a` to `y`)
+const CHAR_COUNT: u64 = (b'z' - b'a') as u64;
+
+/// Letters plus `0` to `8`
+const BASE: u64 = CHAR_COUNT + (b'9' - b'0') as u64;
+
+/// Godot only keeps the low 63 bits so UIDs are always positive
+const ID_MASK: u64 = 0x7FFF_FFFF_FFFF_FFFF;
+
+/// Source of the UIDs written into generated files
+///
+/// Seeded UIDs only depend on the seed, the path of the file they are
+/// written to and how many UIDs that file asked for before, so rendering the
+/// same template twice with the same seed gives identical files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UidGenerator {
+    #[default]
+    Random,
+    Seeded(u64),
+}
+
+impl UidGenerator {
+    /// Returns the `index`-th UID of the file at `path`
+    pub fn id_for(&self, path: &str, index: u64) -> i64 {
+        match self {
+            UidGenerator::Random => random_id(&mut rand::rng()),
+            UidGenerator::Seeded(seed) => {
+                // A fixed mixing function rather than a `rand` RNG, whose
+                // output may change between crate versions
+                let mixed = splitmix64(seed ^ fnv1a(path.as_bytes()))
+                    ^ splitmix64(index.wrapping_add(0x9E37_79B9_7F4A_7C15));
+                (splitmix64(mixed) & ID_MASK) as i64
+            }
+        }
+    }
+}
+
+/// 64-bit FNV-1a hash, stable across platforms and releases
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Finalizer of the SplitMix64 generator
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^ (x >> 31)
+}
+
+/// Picks a random UID the way `ResourceUID::create_id` does
+pub fn random_id(rng: &mut impl Rng) -> i64 {
+    (rng.random::<u64>() & ID_MASK) as i64
+}
+
+/// Encodes a UID like `ResourceUID::id_to_text`
+///
+/// Negative ids are invalid in Godot and encode to `uid://<invalid>`.
+pub fn id_to_text(id: i64) -> String {
+    if id < 0 {
+        return format!("{}<invalid>", PREFIX);
+    }
+
+    let mut id = id as u64;
+    let mut digits = Vec::new();
+    loop {
+        let c = (id % BASE) as u8;
+        digits.push(if (c as u64) < CHAR_COUNT {
+            (b'a' + c) as char
+        } else {
+            (b'0' + c - CHAR_COUNT as u8) as char
+        });
+        id /= BASE;
+        if id == 0 {
+            break;
+        }
+    }
+
+    let text: String = digits.into_iter().rev().collect();
+    format!("{}{}", PREFIX, text)
+}
+
+/// Decodes a `uid://` text like `ResourceUID::text_to_id`
+///
+/// Returns `None` where Godot returns `INVALID_ID`. As in the engine,
+/// digits overflowing 64 bits wrap and the result is masked to 63 bits.
+pub fn text_to_id(text: &str) -> Option<i64> {
+    let digits = text.strip_prefix(PREFIX)?;
+    if digits == "<invalid>" {
+        return None;
+    }
+
+    let mut id: u64 = 0;
+    for c in digits.bytes() {
+        let value = match c {
+            b'a'..=b'z' => (c - b'a') as u64,
+            b'0'..=b'9' => (c - b'0') as u64 + CHAR_COUNT,
+            _ => return None,
+        };
+        id = id.wrapping_mul(BASE).wrapping_add(value);
+    }
+
+    Some((id & ID_MASK) as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_like_godot() {
+        assert_eq!(id_to_text(0), "uid://a");
+        assert_eq!(id_to_text(24), "uid://y");
+        assert_eq!(id_to_text(25), "uid://0");
+        assert_eq!(id_to_text(33), "uid://8");
+        assert_eq!(id_to_text(34), "uid://ba");
+        assert_eq!(id_to_text(-1), "uid://<invalid>");
+    }
+
+    #[test]
+    fn decodes_like_godot() {
+        assert_eq!(text_to_id("uid://ba"), Some(34));
+        assert_eq!(text_to_id("uid://<invalid>"), None);
+        assert_eq!(text_to_id("res://ba"), None);
+        assert_eq!(text_to_id("uid://Ba"), None);
+    }
+
+    #[test]
+    fn round_trips() {
+        let mut rng = rand::rng();
+        let ids = [0, 1, 33, 34, 1_000_000, i64::MAX]
+            .into_iter()
+            .chain((0..1000).map(|_| random_id(&mut rng)));
+
+        for id in ids {
+            let text = id_to_text(id);
+            assert_eq!(text_to_id(&text), Some(id), "{}", text);
+            assert_eq!(id_to_text(text_to_id(&text).unwrap()), text);
+        }
+    }
+
+    #[test]
+    fn seeded_uids_are_deterministic() {
+        let uids = UidGenerator::Seeded(42);
+
+        assert_eq!(uids.id_for("Main.tscn", 0), uids.id_for("Main.tscn", 0));
+        assert_ne!(uids.id_for("Main.tscn", 0), uids.id_for("Main.tscn", 1));
+        assert_ne!(uids.id_for("Main.tscn", 0), uids.id_for("Other.tscn", 0));
+        assert_ne!(
+            uids.id_for("Main.tscn", 0),
+            UidGenerator::Seeded(43).id_for("Main.tscn", 0)
+        );
+        assert!(uids.id_for("Main.tscn", 0) >= 0);
+    }
+
+    #[test]
+    fn generated_uids_are_valid() {
+        for index in 0..100 {
+            let uid = id_to_text(UidGenerator::Random.id_for("Main.tscn", index));
+            assert!(text_to_id(&uid).is_some_and(|id| id >= 0), "{}", uid);
+        }
+    }
 }
@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::utils::paths;
+use crate::utils::registry::Registry;
+
+/// How long a version fetched from the registry is used without asking again
+pub const CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+const CACHE_FILE: &str = "versions.json";
+
+/// Crate versions resolved from the registry, stored in `<cache dir>/versions.json`
+///
+/// Versions are kept per registry: a mirror may not have what crates.io
+/// has, so one registry's answer is never used for another.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct VersionCache {
+    #[serde(skip)]
+    path: Option<PathBuf>,
+    /// Crates by registry URL, as [`Registry`] displays it
+    registries: BTreeMap<String, BTreeMap<String, CachedVersion>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedVersion {
+    pub version: String,
+    /// Seconds since the Unix epoch
+    pub fetched_at: u64,
+    /// Every version published at the time, for resolving requirement pins
+    #[serde(default)]
+    pub published: Vec<String>,
+}
+
+impl CachedVersion {
+    /// Time elapsed since the version was fetched
+    pub fn age(&self) -> Duration {
+        Duration::from_secs(now().saturating_sub(self.fetched_at))
+    }
+
+    pub fn is_fresh(&self) -> bool {
+        self.age() < CACHE_TTL
+    }
+}
+
+impl VersionCache {
+    /// Loads the user's cache; an empty cache when there is none yet
+    pub fn load() -> Self {
+        match paths::cache_dir() {
+            Some(dir) => Self::load_from(dir.join(CACHE_FILE)),
+            None => Self::default(),
+        }
+    }
+
+    /// Loads the cache stored at `path`
+    ///
+    /// A missing or unreadable file gives an empty cache: the cache only
+    /// saves requests, it is never required.
+    pub fn load_from(path: PathBuf) -> Self {
+        let mut cache: Self = fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        cache.path = Some(path);
+        cache
+    }
+
+    pub fn get(&self, registry: &Registry, crate_name: &str) -> Option<&CachedVersion> {
+        self.registries.get(&registry.to_string())?.get(crate_name)
+    }
+
+    /// Records `version` and the `published` versions of `registry` as just
+    /// fetched
+    pub fn insert(
+        &mut self,
+        registry: &Registry,
+        crate_name: &str,
+        version: &str,
+        published: Vec<String>,
+    ) {
+        let crates = self.registries.entry(registry.to_string()).or_default();
+        crates.insert(
+            crate_name.to_string(),
+            CachedVersion {
+                version: version.to_string(),
+                fetched_at: now(),
+                published,
+            },
+        );
+    }
+
+    /// Writes the cache back to the file it was loaded from
+    pub fn save(&self) -> io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let contents = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(path, contents)
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saved_versions_are_loaded_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(CACHE_FILE);
+
+        let crates_io = Registry::default();
+        let mut cache = VersionCache::load_from(path.clone());
+        assert!(cache.get(&crates_io, "godot").is_none());
+
+        cache.insert(
+            &crates_io,
+            "godot",
+            "0.4.0",
+            vec!["0.3.0".to_string(), "0.4.0".to_string()],
+        );
+        cache.save().unwrap();
+
+        let cache = VersionCache::load_from(path);
+        let cached = cache.get(&crates_io, "godot").unwrap();
+        assert_eq!(cached.version, "0.4.0");
+        assert_eq!(cached.published.len(), 2);
+        assert!(cached.is_fresh());
+
+        let mirror = Registry::parse("sparse+http://mirror.example/index/").unwrap();
+        assert!(cache.get(&mirror, "godot").is_none());
+    }
+
+    #[test]
+    fn old_versions_are_stale() {
+        let cached = CachedVersion {
+            version: "0.1.0".to_string(),
+            fetched_at: now() - CACHE_TTL.as_secs() - 1,
+            published: Vec::new(),
+        };
+        assert!(!cached.is_fresh());
+    }
+}